@@ -0,0 +1,59 @@
+//! Generates the solution registry of the main binary.
+//! Every scaffolded `src/bin/<year>_<day>.rs` is included as a module and its `Solver` is added to `SOLUTIONS`.
+use std::{env, fs, path::Path};
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let bin_dir = Path::new(&manifest_dir).join("src").join("bin");
+
+    println!("cargo:rerun-if-changed=src/bin");
+
+    let mut days: Vec<(u16, u8, String)> = fs::read_dir(&bin_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    if path.extension()? != "rs" {
+                        return None;
+                    }
+                    let (year, day) = path.file_stem()?.to_str()?.split_once('_')?;
+                    let year = year.parse::<u16>().ok()?;
+                    let day = day.parse::<u8>().ok()?;
+                    (year >= 2015 && (1..=25).contains(&day))
+                        .then(|| (year, day, path.to_string_lossy().into_owned()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    days.sort_unstable();
+
+    let mut out = String::new();
+
+    for (year, day, path) in &days {
+        out.push_str(&format!(
+            "// lints are reported when the solution is compiled as its own binary.\n\
+             #[cfg(not(test))]\n\
+             #[allow(warnings, clippy::all, clippy::pedantic)]\n\
+             #[path = {path:?}]\n\
+             mod day_{year}_{day:02};\n\n"
+        ));
+    }
+
+    out.push_str(
+        "#[cfg(not(test))]\npub static SOLUTIONS: &advent_of_code::template::Registry = &[\n",
+    );
+    for (year, day, _) in &days {
+        out.push_str(&format!("    &day_{year}_{day:02}::Solver,\n"));
+    }
+    out.push_str("];\n\n");
+
+    // solutions have their own unit tests, don't run them again as part of the main binary.
+    out.push_str(
+        "#[cfg(test)]\npub static SOLUTIONS: &advent_of_code::template::Registry = &[];\n",
+    );
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("solutions.rs"), out).unwrap();
+}
@@ -1,6 +1,6 @@
 use itertools::Itertools;
 
-advent_of_code::solution!(2);
+advent_of_code::solution!(2023, 2);
 
 pub fn part_one(input: &str) -> Option<u32> {
     static LIMITS: &[(&str, u32)] = &[("red", 12), ("blue", 14), ("green", 13)];
@@ -128,13 +128,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 }
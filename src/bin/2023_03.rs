@@ -1,6 +1,6 @@
 use itertools::Itertools;
 
-advent_of_code::solution!(3);
+advent_of_code::solution!(2023, 3);
 
 fn contains_symbols(string: &str) -> bool {
     string.contains(|c: char| !c.is_digit(10) && c != '.')
@@ -259,13 +259,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 }
@@ -1,6 +1,6 @@
 use itertools::Itertools;
 
-advent_of_code::solution!(6);
+advent_of_code::solution!(2023, 6);
 
 fn quadratic(b: f64, c: f64) -> f64 {
     let discrim = ((b * b) - 4.0 * c).sqrt();
@@ -53,13 +53,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 }
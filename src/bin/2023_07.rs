@@ -2,7 +2,7 @@ use std::{cmp::Ordering, collections::HashMap};
 
 use itertools::Itertools;
 
-advent_of_code::solution!(7);
+advent_of_code::solution!(2023, 7);
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 enum HandType {
@@ -182,13 +182,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 }
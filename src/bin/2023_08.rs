@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use num::integer::lcm;
 
-advent_of_code::solution!(8);
+advent_of_code::solution!(2023, 8);
 
 struct Map {
     key: String,
@@ -116,13 +116,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 }
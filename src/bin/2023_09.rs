@@ -1,6 +1,6 @@
 use itertools::Itertools;
 
-advent_of_code::solution!(9);
+advent_of_code::solution!(2023, 9);
 
 #[derive(Clone, Copy)]
 enum Direction {
@@ -90,13 +90,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, None);
     }
 }
@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use rayon::{prelude::ParallelIterator, str::ParallelString};
 
-advent_of_code::solution!(10);
+advent_of_code::solution!(2023, 10);
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 enum Direction {
@@ -239,13 +239,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(8));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(8));
     }
 }
@@ -4,7 +4,7 @@ use rayon::{
     str::ParallelString,
 };
 
-advent_of_code::solution!(11);
+advent_of_code::solution!(2023, 11);
 
 fn convert_to_bool_vec(input: &str) -> Vec<Vec<bool>> {
     let rows: Vec<Vec<bool>> = input
@@ -144,13 +144,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(374));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(1030));
     }
 }
@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use rayon::{prelude::ParallelIterator, str::ParallelString};
 
-advent_of_code::solution!(15);
+advent_of_code::solution!(2023, 15);
 
 fn mutate_char(starting: u32, c: char) -> u32 {
     let mut number = starting;
@@ -108,13 +108,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(1320));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
 
         assert_eq!(result, Some(145));
     }
@@ -8,7 +8,7 @@ use itertools::Itertools;
 use once_cell::sync::Lazy;
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 
-advent_of_code::solution!(16);
+advent_of_code::solution!(2023, 16);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Direction {
@@ -473,13 +473,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(46));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(51));
     }
 }
@@ -1,6 +1,6 @@
 use pathfinding::prelude::{dijkstra, Matrix};
 
-advent_of_code::solution!(17);
+advent_of_code::solution!(2023, 17);
 
 fn dijkstra_wrapper_wrapper(input: &str, min_moves: u32, max_moves: u32) -> u32 {
     let lava_matrix = Matrix::from_rows(
@@ -60,13 +60,13 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(102));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&advent_of_code::template::read_file("examples", YEAR, DAY));
         assert_eq!(result, Some(71));
     }
 }
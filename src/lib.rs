@@ -1,4 +1,6 @@
 mod day;
 pub mod template;
+mod year;
 
 pub use day::*;
+pub use year::*;
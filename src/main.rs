@@ -1,92 +1,292 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve};
+use advent_of_code::template::commands::{
+    all, completions, download, examples, help, next, read, scaffold, solve, status, test,
+};
+use advent_of_code::template::runner::{BenchConfig, RunOptions};
+use advent_of_code::template::Error;
 use args::{parse, AppArguments};
 
+/// Registry of all scaffolded solutions, generated by `build.rs`.
+mod solutions {
+    include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+}
+
 mod args {
-    use std::process;
+    use std::time::Duration;
 
-    use advent_of_code::Day;
+    use advent_of_code::template::baselines::{self, BaselineOptions};
+    use advent_of_code::template::commands::{completions::Shell, help};
+    use advent_of_code::template::runner::{parse_duration, OutputFormat};
+    use advent_of_code::template::InputSource;
+    use advent_of_code::{Day, Year};
 
     pub enum AppArguments {
+        Completions {
+            year: Year,
+            shell: Shell,
+        },
         Download {
+            year: Year,
             day: Day,
         },
+        Examples {
+            year: Year,
+            day: Day,
+        },
+        Help {
+            command: Option<String>,
+        },
+        Next {
+            year: Year,
+        },
         Read {
+            year: Year,
             day: Day,
         },
         Scaffold {
+            year: Year,
             day: Day,
         },
         Solve {
+            year: Year,
             day: Day,
             release: bool,
             time: bool,
+            budget: Option<u64>,
+            warmup: Option<u32>,
             submit: Option<u8>,
+            format: OutputFormat,
+            input: InputSource,
+            timeout: Option<Duration>,
+            watch: bool,
+        },
+        Status {
+            year: Year,
+        },
+        Test {
+            year: Year,
+            day: Day,
+            release: bool,
+            watch: bool,
         },
         All {
+            year: Year,
             release: bool,
             time: bool,
+            budget: Option<u64>,
+            warmup: Option<u32>,
+            format: OutputFormat,
+            jobs: Option<usize>,
+            check: bool,
+            timeout: Option<Duration>,
+            baselines: BaselineOptions,
         },
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
 
-        let app_args = match args.subcommand()?.as_deref() {
-            Some("all") => AppArguments::All {
+        let subcommand = args.subcommand()?;
+
+        if args.contains(["-h", "--help"]) {
+            return Ok(AppArguments::Help {
+                command: subcommand,
+            });
+        }
+
+        let command = match subcommand.as_deref() {
+            Some("help") => {
+                return Ok(AppArguments::Help {
+                    command: args.opt_free_from_str()?,
+                })
+            }
+            Some(name) => help::find(name).ok_or_else(|| {
+                format!("Unknown command: {name}. Run `cargo run -- help` to list the commands.")
+            })?,
+            None => return Ok(AppArguments::Help { command: None }),
+        };
+
+        parse_command(command.name, args).map_err(|e| {
+            format!(
+                "{e}\n\nUsage: {}\nRun `cargo {} --help` for more information.",
+                help::format_usage(command),
+                command.alias
+            )
+            .into()
+        })
+    }
+
+    fn parse_command(
+        subcommand: &str,
+        mut args: pico_args::Arguments,
+    ) -> Result<AppArguments, Box<dyn std::error::Error>> {
+        // parsed before any free-standing argument, so that it can be passed in any position.
+        let year = args
+            .opt_value_from_str("--year")?
+            .or_else(Year::from_env)
+            .ok_or("no year specified, pass --year or set AOC_YEAR in .cargo/config.toml")?;
+
+        let app_args = match subcommand {
+            "all" => AppArguments::All {
+                year,
                 release: args.contains("--release"),
                 time: args.contains("--time"),
+                budget: args.opt_value_from_str("--budget")?,
+                warmup: args.opt_value_from_str("--warmup")?,
+                format: args.opt_value_from_str("--format")?.unwrap_or_default(),
+                jobs: args.opt_value_from_str("--jobs")?,
+                check: args.contains("--check"),
+                timeout: args.opt_value_from_fn("--timeout", parse_duration)?,
+                baselines: BaselineOptions {
+                    compare: args.opt_value_from_fn("--baseline", parse_baseline_name)?,
+                    save: args.opt_value_from_fn("--save-baseline", parse_baseline_name)?,
+                },
+            },
+            "download" => AppArguments::Download {
+                year,
+                day: args.free_from_str()?,
             },
-            Some("download") => AppArguments::Download {
+            "examples" => AppArguments::Examples {
+                year,
                 day: args.free_from_str()?,
             },
-            Some("read") => AppArguments::Read {
+            "next" => AppArguments::Next { year },
+            "read" => AppArguments::Read {
+                year,
                 day: args.free_from_str()?,
             },
-            Some("scaffold") => AppArguments::Scaffold {
+            "scaffold" => AppArguments::Scaffold {
+                year,
                 day: args.free_from_str()?,
             },
-            Some("solve") => AppArguments::Solve {
+            "solve" => AppArguments::Solve {
+                year,
                 day: args.free_from_str()?,
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 time: args.contains("--time"),
+                budget: args.opt_value_from_str("--budget")?,
+                warmup: args.opt_value_from_str("--warmup")?,
+                format: args.opt_value_from_str("--format")?.unwrap_or_default(),
+                input: {
+                    let is_example = args.contains("--example");
+                    args.opt_value_from_str("--input")?
+                        .unwrap_or(if is_example {
+                            InputSource::Example
+                        } else {
+                            InputSource::Puzzle
+                        })
+                },
+                timeout: args.opt_value_from_fn("--timeout", parse_duration)?,
+                watch: args.contains("--watch"),
             },
-            Some(x) => {
-                eprintln!("Unknown command: {x}");
-                process::exit(1);
-            }
-            None => {
-                eprintln!("No command specified.");
-                process::exit(1);
-            }
+            "status" => AppArguments::Status { year },
+            "test" => AppArguments::Test {
+                year,
+                day: args.free_from_str()?,
+                release: args.contains("--release"),
+                watch: args.contains("--watch"),
+            },
+            "completions" => AppArguments::Completions {
+                year,
+                shell: args.free_from_str()?,
+            },
+            _ => unreachable!("every command of `help::COMMANDS` is parsed"),
         };
 
         let remaining = args.finish();
         if !remaining.is_empty() {
-            eprintln!("Warning: unknown argument(s): {remaining:?}.");
+            return Err(format!("unknown argument(s): {remaining:?}.").into());
         }
 
         Ok(app_args)
     }
+
+    fn parse_baseline_name(name: &str) -> Result<String, String> {
+        baselines::check_name(name)
+            .map(|()| name.to_string())
+            .map_err(|e| e.to_string())
+    }
 }
 
 fn main() {
-    match parse() {
-        Err(err) => {
-            eprintln!("Error: {err}");
-            std::process::exit(1);
-        }
+    let result = match parse() {
+        Err(err) => Err(Error::Usage(err.to_string())),
         Ok(args) => match args {
-            AppArguments::All { release, time } => all::handle(release, time),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold { day } => scaffold::handle(day),
+            AppArguments::All {
+                year,
+                release,
+                time,
+                budget,
+                warmup,
+                format,
+                jobs,
+                check,
+                timeout,
+                baselines,
+            } => all::handle(
+                solutions::SOLUTIONS,
+                year,
+                release,
+                RunOptions {
+                    bench: time.then(|| BenchConfig::with_overrides(budget, warmup)),
+                    format,
+                    timeout,
+                    ..Default::default()
+                },
+                jobs,
+                check,
+                &baselines,
+            ),
+            AppArguments::Completions { year, shell } => {
+                completions::handle(shell, year);
+                Ok(())
+            }
+            AppArguments::Download { year, day } => download::handle(year, day),
+            AppArguments::Examples { year, day } => examples::handle(year, day),
+            AppArguments::Help { command } => help::handle(command.as_deref()),
+            AppArguments::Next { year } => next::handle(year),
+            AppArguments::Read { year, day } => read::handle(year, day),
+            AppArguments::Scaffold { year, day } => scaffold::handle(year, day),
             AppArguments::Solve {
+                year,
                 day,
                 release,
                 time,
+                budget,
+                warmup,
                 submit,
-            } => solve::handle(day, release, time, submit),
+                format,
+                input,
+                timeout,
+                watch,
+            } => solve::handle(
+                year,
+                day,
+                release,
+                &RunOptions {
+                    bench: time.then(|| BenchConfig::with_overrides(budget, warmup)),
+                    format,
+                    submit,
+                    timeout,
+                    ..Default::default()
+                },
+                &input,
+                watch,
+            ),
+            AppArguments::Status { year } => {
+                status::handle(year);
+                Ok(())
+            }
+            AppArguments::Test {
+                year,
+                day,
+                release,
+                watch,
+            } => test::handle(year, day, release, watch),
         },
     };
+
+    if let Err(e) = result {
+        e.exit();
+    }
 }
@@ -0,0 +1,164 @@
+/// Module that reads the accepted answers of each day from `data/<year>/answers/<day>.toml`.
+///
+/// ```toml
+/// part_one = 142
+/// part_two = "281"
+/// ```
+///
+/// Answers may be written as strings or integers, parts without an accepted answer are left out.
+/// The answer of a part is saved automatically when a submission of it is correct.
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::template::Error;
+use crate::{Day, Year};
+
+/// The accepted answers of a day.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answers {
+    #[serde(default, deserialize_with = "answer::deserialize")]
+    pub part_one: Option<String>,
+    #[serde(default, deserialize_with = "answer::deserialize")]
+    pub part_two: Option<String>,
+}
+
+impl Answers {
+    /// Returns the accepted answer for `part`, if there is one.
+    #[must_use]
+    pub fn get(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_one.as_deref(),
+            2 => self.part_two.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Parses answers from the contents of an answers file.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        toml::from_str(s).map_err(|e| Error::Parser(format!("could not parse answers: {e}")))
+    }
+}
+
+mod answer {
+    use serde::{Deserialize, Deserializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Answer {
+        String(String),
+        Integer(i64),
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<String>, D::Error> {
+        Ok(
+            Option::<Answer>::deserialize(deserializer)?.map(|answer| match answer {
+                Answer::String(s) => s,
+                Answer::Integer(i) => i.to_string(),
+            }),
+        )
+    }
+}
+
+#[must_use]
+pub fn get_path(year: Year, day: Day) -> String {
+    format!("data/{year}/answers/{day}.toml")
+}
+
+/// Reads the accepted answers of a day. Returns [`None`] if no answers were recorded.
+pub fn read(year: Year, day: Day) -> Result<Option<Answers>, Error> {
+    let path = get_path(year, day);
+
+    if !Path::new(&path).exists() {
+        return Ok(None);
+    }
+
+    let contents =
+        fs::read_to_string(&path).map_err(Error::io(format!("could not read \"{path}\"")))?;
+    Answers::parse(&contents).map(Some)
+}
+
+/// Records `answer` as the accepted answer of `part`.
+/// Only the line of the part is written, other lines of the file, including comments, are kept as they are.
+pub fn save(year: Year, day: Day, part: u8, answer: &str) -> Result<(), Error> {
+    let path = get_path(year, day);
+    let context = format!("could not write \"{path}\"");
+
+    let key = match part {
+        1 => "part_one",
+        2 => "part_two",
+        _ => return Err(Error::Usage(format!("Day {day} has no part {part}."))),
+    };
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(Error::io(format!("could not read \"{path}\""))(e)),
+    };
+
+    if let Some(parent) = Path::new(&path).parent() {
+        fs::create_dir_all(parent).map_err(Error::io(&context))?;
+    }
+    fs::write(&path, set_answer(&contents, key, answer)).map_err(Error::io(context))
+}
+
+/// Replaces the line of `key` in the contents of an answers file with `answer`, or adds it at the end.
+fn set_answer(contents: &str, key: &str, answer: &str) -> String {
+    let line = format!("{key} = {}", toml::Value::String(answer.to_string()));
+    let mut lines: Vec<&str> = contents.lines().collect();
+
+    match lines
+        .iter()
+        .position(|x| x.split('=').next().is_some_and(|x| x.trim() == key))
+    {
+        Some(i) => lines[i] = &line,
+        None => lines.push(&line),
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{set_answer, Answers};
+
+    #[test]
+    fn parses_strings_and_integers() {
+        let answers = Answers::parse("part_one = 142\npart_two = \"abc\"\n").unwrap();
+        assert_eq!(answers.get(1), Some("142"));
+        assert_eq!(answers.get(2), Some("abc"));
+    }
+
+    #[test]
+    fn parses_missing_parts() {
+        let answers = Answers::parse("part_one = \"142\"").unwrap();
+        assert_eq!(answers.get(1), Some("142"));
+        assert_eq!(answers.get(2), None);
+        assert_eq!(Answers::parse("").unwrap(), Answers::default());
+    }
+
+    #[test]
+    fn sets_answer_keeping_other_lines() {
+        let contents = "# checked by hand\npart_one = 142\n";
+        let contents = set_answer(contents, "part_two", "281");
+        assert_eq!(
+            contents,
+            "# checked by hand\npart_one = 142\npart_two = \"281\"\n"
+        );
+        assert_eq!(
+            set_answer(&contents, "part_one", "143"),
+            "# checked by hand\npart_one = \"143\"\npart_two = \"281\"\n"
+        );
+
+        let contents = set_answer("", "part_one", "a\"b");
+        assert_eq!(Answers::parse(&contents).unwrap().get(1), Some("a\"b"));
+    }
+
+    #[test]
+    fn errors_on_invalid_answers() {
+        assert!(Answers::parse("part_one = [1, 2]").is_err());
+        assert!(Answers::parse("part_one = ").is_err());
+    }
+}
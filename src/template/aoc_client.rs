@@ -0,0 +1,245 @@
+/// Module that fetches inputs and puzzle descriptions from the Advent of Code website, without the need for aoc-cli.
+///
+/// Requests are authenticated with the session cookie of the website, read from the `AOC_SESSION` environment
+/// variable or from `.adventofcode.session` in the home directory, the file that aoc-cli uses as well.
+/// The website can be replaced with a local server by setting `AOC_BASE_URL`.
+/// The website asks automated tools to identify themselves and their operator, set `AOC_USER_AGENT` to do so.
+use std::{env, fs, path::PathBuf, time::Duration};
+
+use crate::template::Error;
+use crate::{Day, Year};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// User agent of requests if `AOC_USER_AGENT` is not set, it identifies the template but not its operator.
+pub const DEFAULT_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+const SESSION_FILE: &str = ".adventofcode.session";
+
+/// An authenticated client of the Advent of Code website.
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    #[must_use]
+    pub fn new(base_url: &str, session: &str, user_agent: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            agent: ureq::AgentBuilder::new()
+                .user_agent(user_agent)
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    /// Creates a client for `AOC_BASE_URL`, or the website if it is not set, with the session from [`read_session`]
+    /// and the user agent from `AOC_USER_AGENT`.
+    pub fn from_env() -> Result<Self, Error> {
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.into());
+        let user_agent = env::var("AOC_USER_AGENT")
+            .ok()
+            .filter(|x| !x.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_USER_AGENT.into());
+        Ok(Self::new(&base_url, &read_session()?, user_agent.trim()))
+    }
+
+    /// Fetches the puzzle input of `day`.
+    pub fn get_input(&self, year: Year, day: Day) -> Result<String, Error> {
+        self.get(
+            year,
+            day,
+            &format!("/{year}/day/{}/input", day.into_inner()),
+        )
+    }
+
+    /// Fetches the HTML page of the puzzle of `day`. It contains the description of all parts unlocked so far.
+    pub fn get_puzzle(&self, year: Year, day: Day) -> Result<String, Error> {
+        self.get(year, day, &format!("/{year}/day/{}", day.into_inner()))
+    }
+
+    fn get(&self, year: Year, day: Day, path: &str) -> Result<String, Error> {
+        let url = format!("{}{path}", self.base_url);
+
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call();
+
+        match response {
+            Ok(response) => response
+                .into_string()
+                .map_err(Error::io(format!("could not read response of {url}"))),
+            Err(ureq::Error::Status(status, response)) => {
+                let retry_after = response
+                    .header("Retry-After")
+                    .and_then(|x| x.parse().ok())
+                    .map(Duration::from_secs);
+                let body = response.into_string().unwrap_or_default();
+                Err(get_status_error(
+                    status,
+                    &body,
+                    retry_after,
+                    year,
+                    day,
+                    &url,
+                ))
+            }
+            Err(ureq::Error::Transport(e)) => {
+                Err(Error::Http(format!("could not reach {url}: {e}")))
+            }
+        }
+    }
+}
+
+/// Maps an error response of the website to the matching [`Error`].
+fn get_status_error(
+    status: u16,
+    body: &str,
+    retry_after: Option<Duration>,
+    year: Year,
+    day: Day,
+    url: &str,
+) -> Error {
+    match status {
+        // the website answers requests with an invalid session with 400 and, for malformed cookies, with 500.
+        400 | 401 | 403 => Error::SessionExpired,
+        500 if body.contains("log in") => Error::SessionExpired,
+        404 => Error::NotUnlocked(year, day),
+        429 => Error::RateLimited(retry_after),
+        _ => Error::Http(format!("{url} responded with status {status}")),
+    }
+}
+
+/// Reads the session cookie from `AOC_SESSION`, or from `.adventofcode.session` in the home directory.
+pub fn read_session() -> Result<String, Error> {
+    let session = match env::var("AOC_SESSION") {
+        Ok(session) => session,
+        Err(_) => {
+            let Some(path) = get_session_path() else {
+                return Err(Error::SessionMissing);
+            };
+            match fs::read_to_string(&path) {
+                Ok(session) => session,
+                Err(_) => return Err(Error::SessionMissing),
+            }
+        }
+    };
+
+    match session.trim() {
+        "" => Err(Error::SessionMissing),
+        session => Ok(session.to_string()),
+    }
+}
+
+fn get_session_path() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(SESSION_FILE))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Client, DEFAULT_USER_AGENT};
+    use crate::template::Error;
+    use crate::{day, year};
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// Serves a single request with `status` and `body`, and returns the head of the request.
+    fn serve(status: &'static str, body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                request.push_str(&line);
+            }
+
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nRetry-After: 60\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+
+            request
+        });
+
+        (url, handle)
+    }
+
+    #[test]
+    fn fetches_input_with_session() {
+        let (url, server) = serve("200 OK", "1\n2\n3\n");
+        let client = Client::new(
+            &url,
+            "secret",
+            "github.com/someone/aoc by someone@example.com",
+        );
+
+        let input = client.get_input(year!(2023), day!(5)).unwrap();
+        let request = server.join().unwrap();
+
+        assert_eq!(input, "1\n2\n3\n");
+        assert!(request.starts_with("GET /2023/day/5/input HTTP/1.1"));
+        assert!(request.contains("Cookie: session=secret"));
+        assert!(request.contains("User-Agent: github.com/someone/aoc by someone@example.com"));
+    }
+
+    #[test]
+    fn detects_expired_session() {
+        let (url, server) = serve(
+            "400 Bad Request",
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+        );
+        let result =
+            Client::new(&url, "expired", DEFAULT_USER_AGENT).get_input(year!(2023), day!(1));
+        server.join().unwrap();
+
+        assert!(matches!(result, Err(Error::SessionExpired)));
+    }
+
+    #[test]
+    fn detects_locked_puzzle() {
+        let (url, server) = serve(
+            "404 Not Found",
+            "Please don't repeatedly request this endpoint before it unlocks!",
+        );
+        let result =
+            Client::new(&url, "secret", DEFAULT_USER_AGENT).get_puzzle(year!(2023), day!(25));
+        server.join().unwrap();
+
+        assert!(
+            matches!(result, Err(Error::NotUnlocked(year, day)) if year == 2023 && day == day!(25))
+        );
+    }
+
+    #[test]
+    fn detects_rate_limiting() {
+        let (url, server) = serve("429 Too Many Requests", "");
+        let result =
+            Client::new(&url, "secret", DEFAULT_USER_AGENT).get_puzzle(year!(2023), day!(1));
+        server.join().unwrap();
+
+        assert!(matches!(
+            result,
+            Err(Error::RateLimited(Some(duration))) if duration.as_secs() == 60
+        ));
+    }
+}
@@ -0,0 +1,280 @@
+/// Module that stores named benchmark baselines in `data/<year>/baselines/<name>.jsonl` and compares runs against them.
+/// A baseline holds the records of all benched parts, in the same format as `--format json`.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+use std::{fs, path::Path};
+
+use crate::template::runner::{self, PartRecord};
+use crate::template::{Error, ANSI_BOLD, ANSI_GREEN, ANSI_RED, ANSI_RESET};
+use crate::{Day, Year};
+
+/// Changes within this many percent are considered noise and are not highlighted.
+const NOISE_THRESHOLD: f64 = 5.0;
+
+/// The baselines to compare a run against and to save it as, set with `--baseline` and `--save-baseline`.
+#[derive(Debug, Clone, Default)]
+pub struct BaselineOptions {
+    pub compare: Option<String>,
+    pub save: Option<String>,
+}
+
+impl BaselineOptions {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.compare.is_none() && self.save.is_none()
+    }
+}
+
+#[must_use]
+pub fn get_path(year: Year, name: &str) -> String {
+    format!("data/{year}/baselines/{name}.jsonl")
+}
+
+/// Baseline names end up in a file name, so they are limited to letters, digits, `-`, `_` and `.`.
+pub fn check_name(name: &str) -> Result<(), Error> {
+    let is_valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::Usage(format!(
+            "invalid baseline name \"{name}\", use letters, digits, \"-\", \"_\" and \".\" only."
+        )))
+    }
+}
+
+/// Stores the benched parts of `records` as the baseline `name`, replacing an existing baseline of that name.
+pub fn write(year: Year, name: &str, records: &[PartRecord]) -> Result<(), Error> {
+    let path = get_path(year, name);
+    let context = format!("could not write baseline \"{path}\"");
+
+    if let Some(parent) = Path::new(&path).parent() {
+        fs::create_dir_all(parent).map_err(Error::io(&context))?;
+    }
+
+    let contents: String = records
+        .iter()
+        .filter(|record| record.stats.is_some())
+        .map(|record| record.to_json() + "\n")
+        .collect();
+
+    fs::write(&path, contents).map_err(Error::io(context))
+}
+
+/// Reads the baseline `name`.
+pub fn read(year: Year, name: &str) -> Result<Vec<PartRecord>, Error> {
+    let path = get_path(year, name);
+    let contents = fs::read_to_string(&path)
+        .map_err(Error::io(format!("could not read baseline \"{path}\"")))?;
+    Ok(contents.lines().filter_map(PartRecord::from_json).collect())
+}
+
+/// The timing of a part in a baseline and in the current run.
+#[derive(Debug, PartialEq)]
+pub struct Comparison {
+    pub day: Day,
+    pub part: u8,
+    pub baseline: Option<Duration>,
+    pub current: Option<Duration>,
+}
+
+impl Comparison {
+    /// Change from the baseline to the current run in percent. Negative values are improvements.
+    #[must_use]
+    pub fn change(&self) -> Option<f64> {
+        let (baseline, current) = (self.baseline?, self.current?);
+        if baseline.is_zero() {
+            return None;
+        }
+        Some((current.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0)
+    }
+}
+
+/// Pairs the benched parts of `baseline` and `current`, ordered by day and part.
+#[must_use]
+pub fn compare(baseline: &[PartRecord], current: &[PartRecord]) -> Vec<Comparison> {
+    let mut parts: BTreeMap<(Day, u8), (Option<Duration>, Option<Duration>)> = BTreeMap::new();
+
+    for record in baseline.iter().filter(|x| x.stats.is_some()) {
+        parts.entry((record.day, record.part)).or_default().0 = Some(record.duration());
+    }
+
+    for record in current.iter().filter(|x| x.stats.is_some()) {
+        parts.entry((record.day, record.part)).or_default().1 = Some(record.duration());
+    }
+
+    parts
+        .into_iter()
+        .map(|((day, part), (baseline, current))| Comparison {
+            day,
+            part,
+            baseline,
+            current,
+        })
+        .collect()
+}
+
+/// Formats `comparisons` as a table. Regressions are printed in red, improvements in green.
+#[must_use]
+pub fn format_comparisons(name: &str, comparisons: &[Comparison]) -> String {
+    let rows: Vec<[String; 5]> = comparisons
+        .iter()
+        .map(|comparison| {
+            let part = if comparison.part == runner::PARSE {
+                "Parse".to_string()
+            } else {
+                comparison.part.to_string()
+            };
+
+            [
+                comparison.day.to_string(),
+                part,
+                format_duration(comparison.baseline),
+                format_duration(comparison.current),
+                format_change(comparison),
+            ]
+        })
+        .collect();
+
+    let headers = [
+        "Day".to_string(),
+        "Part".to_string(),
+        name.to_string(),
+        "Current".to_string(),
+        "Change".to_string(),
+    ];
+
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|i| {
+            rows.iter()
+                .chain([&headers])
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{ANSI_BOLD}{}{ANSI_RESET}",
+        format_row(&headers, &widths)
+    );
+
+    for (row, comparison) in rows.iter().zip(comparisons) {
+        let color = match comparison.change() {
+            Some(change) if change > NOISE_THRESHOLD => ANSI_RED,
+            Some(change) if change < -NOISE_THRESHOLD => ANSI_GREEN,
+            _ => "",
+        };
+        let reset = if color.is_empty() { "" } else { ANSI_RESET };
+        let _ = writeln!(
+            out,
+            "{}  {color}{}{reset}",
+            format_row(&row[..4], &widths),
+            row[4]
+        );
+    }
+
+    out
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+    duration.map_or_else(|| "-".into(), |x| format!("{x:.1?}"))
+}
+
+fn format_change(comparison: &Comparison) -> String {
+    match (comparison.baseline, comparison.current) {
+        (None, Some(_)) => "new".into(),
+        (Some(_), None) => "missing".into(),
+        _ => comparison
+            .change()
+            .map_or_else(|| "-".into(), |change| format!("{change:+.1}%")),
+    }
+}
+
+fn format_row(cells: &[String], widths: &[usize]) -> String {
+    cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, &width)| format!("{cell:<width$}"))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{check_name, compare, format_comparisons, Comparison};
+    use crate::day;
+    use crate::template::runner::PartRecord;
+    use crate::template::{ANSI_GREEN, ANSI_RED};
+    use std::time::Duration;
+
+    fn record(day: u8, part: u8, duration_ns: u64) -> PartRecord {
+        PartRecord::from_json(&format!(
+            r#"{{"day":{day},"part":{part},"answer":"1","duration_ns":{duration_ns},"samples":10,"status":"solved","stats":{{"samples":10,"outliers":0,"mean_ns":{duration_ns},"min_ns":{duration_ns},"median_ns":{duration_ns},"p95_ns":{duration_ns},"std_dev_ns":0}}}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn checks_names() {
+        assert!(check_name("before-memo_2").is_ok());
+        assert!(check_name("").is_err());
+        assert!(check_name("../README").is_err());
+        assert!(check_name(".hidden").is_err());
+    }
+
+    #[test]
+    fn compares_parts() {
+        let baseline = [record(8, 1, 1000), record(8, 2, 2000)];
+        let current = [record(8, 1, 500), record(16, 1, 100)];
+
+        let comparisons = compare(&baseline, &current);
+
+        assert_eq!(
+            comparisons,
+            vec![
+                Comparison {
+                    day: day!(8),
+                    part: 1,
+                    baseline: Some(Duration::from_nanos(1000)),
+                    current: Some(Duration::from_nanos(500)),
+                },
+                Comparison {
+                    day: day!(8),
+                    part: 2,
+                    baseline: Some(Duration::from_nanos(2000)),
+                    current: None,
+                },
+                Comparison {
+                    day: day!(16),
+                    part: 1,
+                    baseline: None,
+                    current: Some(Duration::from_nanos(100)),
+                },
+            ]
+        );
+        assert_eq!(comparisons[0].change(), Some(-50.0));
+        assert_eq!(comparisons[1].change(), None);
+    }
+
+    #[test]
+    fn colors_changes() {
+        let baseline = [record(1, 1, 1000), record(1, 2, 1000), record(2, 1, 1000)];
+        let current = [record(1, 1, 1500), record(1, 2, 800), record(2, 1, 1010)];
+
+        let table = format_comparisons("before", &compare(&baseline, &current));
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert!(lines[0].contains("before"));
+        assert!(lines[1].contains(&format!("{ANSI_RED}+50.0%")));
+        assert!(lines[2].contains(&format!("{ANSI_GREEN}-20.0%")));
+        assert!(lines[3].ends_with("  +1.0%"));
+    }
+}
@@ -1,193 +1,373 @@
-use std::io;
+use std::{
+    collections::BTreeMap,
+    io::{stdout, Write},
+    num::NonZeroUsize,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
 
 use crate::template::{
+    alloc,
+    baselines::{self, BaselineOptions},
+    encryption, get_bin_name,
     readme_benchmarks::{self, Timings},
-    ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+    runner::{self, OutputFormat, PartRecord, RunOptions},
+    Error, Registry, Solution, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
 };
-use crate::{all_days, Day};
-
-pub fn handle(is_release: bool, is_timed: bool) {
-    let mut timings: Vec<Timings> = vec![];
+use crate::{all_days, Day, Year};
+
+/// Runs every solution of `year` in `solutions` in-process. Parts are benched if `options.bench` is set.
+/// With more than one job, days run in parallel and their output is printed in order once available.
+/// With `baselines`, the timings are compared against and/or saved as a named baseline.
+/// Fails if a part panicked or timed out. With `check`, it also fails if a part no longer returns its accepted answer.
+/// Parts that exceed `options.timeout` are skipped, their days are listed at the end and left out of the README.
+pub fn handle(
+    solutions: &Registry,
+    year: Year,
+    is_release: bool,
+    options: RunOptions,
+    jobs: Option<usize>,
+    check: bool,
+    baselines: &BaselineOptions,
+) -> Result<(), Error> {
+    let is_timed = options.bench.is_some();
+    let format = options.format;
+
+    if !baselines.is_empty() && !is_timed {
+        return Err(Error::Usage(
+            "--baseline and --save-baseline can only be used together with --time.".into(),
+        ));
+    }
 
-    all_days().for_each(|day| {
-        if day > 1 {
-            println!();
-        }
+    // read before running, so that a missing baseline fails fast.
+    let baseline = match &baselines.compare {
+        Some(name) => Some((name, baselines::read(year, name)?)),
+        None => None,
+    };
 
-        println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-        println!("------");
+    // benchmarks and allocation counts of days running in parallel would interfere with each other.
+    let is_sequential = is_timed || alloc::is_enabled();
 
-        let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+    if is_sequential && jobs.is_some_and(|jobs| jobs > 1) {
+        let reason = if is_timed {
+            "--time"
+        } else {
+            "the alloc_stats feature"
+        };
+        return Err(Error::Usage(format!(
+            "--jobs can not be greater than 1 with {reason}, since days running in parallel would skew each other's measurements."
+        )));
+    }
 
-        if output.is_empty() {
-            println!("Not solved.");
+    let jobs = jobs.unwrap_or_else(|| {
+        if is_sequential {
+            1
         } else {
-            let val = child_commands::parse_exec_time(&output, day);
-            timings.push(val);
+            thread::available_parallelism().map_or(1, NonZeroUsize::get)
         }
     });
 
+    let options = RunOptions {
+        submit: None,
+        // parallel runs buffer the output of each day and print it themselves.
+        quiet: jobs > 1,
+        verify: true,
+        ..options
+    };
+
+    let days: Vec<Day> = all_days(year).collect();
+
+    let results = if jobs > 1 {
+        run_parallel(solutions, year, &days, &options, jobs)
+    } else {
+        days.iter()
+            .map(|&day| run_day(solutions, year, day, &options, &mut stdout()))
+            .collect()
+    };
+
+    let timings: Vec<Timings> = days
+        .iter()
+        .zip(&results)
+        .filter(|(_, records)| !records.is_empty())
+        .filter(|(_, records)| !records.iter().any(PartRecord::is_timed_out))
+        .map(|(&day, records)| {
+            let parts = find_solution(solutions, year, day).map_or(2, |x| x.parts());
+            get_timings(records, day, parts)
+        })
+        .collect();
+
     if is_timed {
         let total_millis = timings.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64;
 
-        println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
+        if format == OutputFormat::Human {
+            println!(
+                "\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
+            );
+        }
 
         if is_release {
-            match readme_benchmarks::update(timings, total_millis) {
-                Ok(()) => println!("Successfully updated README with benchmarks."),
-                Err(_) => {
-                    eprintln!("Failed to update readme with benchmarks.");
+            match readme_benchmarks::update(year, timings, total_millis) {
+                Ok(()) => {
+                    if format == OutputFormat::Human {
+                        println!("Successfully updated README with benchmarks.");
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Failed to update readme with benchmarks: {e}");
                 }
             }
         }
     }
-}
 
-#[derive(Debug)]
-pub enum Error {
-    BrokenPipe,
-    Parser(String),
-    IO(io::Error),
-}
+    let records = results.concat();
 
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
-        Error::IO(e)
+    if let Some((name, baseline)) = baseline {
+        if format == OutputFormat::Human {
+            let comparisons = baselines::compare(&baseline, &records);
+            println!("\n{}", baselines::format_comparisons(name, &comparisons));
+        }
     }
-}
 
-#[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
-}
+    if let Some(name) = &baselines.save {
+        baselines::write(year, name, &records)?;
+        if format == OutputFormat::Human {
+            println!("Saved baseline \"{name}\".");
+        }
+    }
 
-/// All solutions live in isolated binaries.
-/// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
-mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::Day;
-    use std::{
-        io::{BufRead, BufReader},
-        path::Path,
-        process::{Command, Stdio},
-        thread,
-    };
+    print_failures(&results, "Timed out", PartRecord::is_timed_out);
+    print_failures(&results, "Panicked", PartRecord::is_panicked);
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
-        // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+    if check {
+        let regressions: Vec<&PartRecord> = results
+            .iter()
+            .flatten()
+            .filter(|x| x.is_regression())
+            .collect();
+
+        if !regressions.is_empty() {
+            eprintln!("\n{ANSI_BOLD}Regressions:{ANSI_RESET}");
+            for record in regressions {
+                eprintln!(
+                    "Day {} Part {}: expected {}, got {}",
+                    record.day,
+                    record.part,
+                    record.expected.as_deref().unwrap_or_default(),
+                    record.answer.as_deref().unwrap_or("✖")
+                );
+            }
         }
+    }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+    runner::check_records(&records, check)
+}
 
-        if is_release {
-            args.push("--release");
-        }
+/// Prints a summary of the parts that match `filter` to stderr, if there are any.
+fn print_failures(results: &[Vec<PartRecord>], title: &str, filter: fn(&PartRecord) -> bool) {
+    let failures: Vec<&PartRecord> = results.iter().flatten().filter(|x| filter(x)).collect();
 
-        if is_timed {
-            // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
-        }
+    if failures.is_empty() {
+        return;
+    }
 
-        // spawn child command with piped stdout/stderr.
-        // forward output to stdout/stderr while grabbing stdout lines.
+    eprintln!("\n{ANSI_BOLD}{title}:{ANSI_RESET}");
 
-        let mut cmd = Command::new("cargo")
-            .args(&args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
+    for record in failures {
+        let label = if record.part == runner::PARSE {
+            format!("Day {} Parse", record.day)
+        } else {
+            format!("Day {} Part {}", record.day, record.part)
+        };
 
-        let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
-        let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
+        match &record.panic {
+            Some(message) => eprintln!("{label}: {message}"),
+            None => eprintln!("{label}"),
+        }
+    }
+}
 
-        let mut output = vec![];
+/// Collects the timings of all benched parts of a day with `parts` puzzle parts.
+fn get_timings(records: &[PartRecord], day: Day, parts: u8) -> Timings {
+    let mut timings = Timings {
+        day,
+        is_single_part: parts == 1,
+        parse: None,
+        part_1: None,
+        part_2: None,
+        total_nanos: 0_f64,
+    };
 
-        let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
-            });
+    records
+        .iter()
+        .filter(|record| record.stats.is_some())
+        .for_each(|record| {
+            let timing_str = Some(format!("{:.1?}", record.duration()));
+
+            match record.part {
+                runner::PARSE => timings.parse = timing_str,
+                1 => timings.part_1 = timing_str,
+                2 => timings.part_2 = timing_str,
+                _ => {}
+            }
+
+            #[allow(clippy::cast_precision_loss)]
+            let nanos = record.duration_ns as f64;
+            timings.total_nanos += nanos;
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
+    timings
+}
+
+#[must_use]
+pub fn get_path_for_bin(year: Year, day: Day) -> String {
+    format!("./src/bin/{}.rs", get_bin_name(year, day))
+}
+
+#[must_use]
+pub fn get_path_for_input(year: Year, day: Day) -> String {
+    format!("data/{year}/inputs/{day}.txt")
+}
+
+/// Runs `days` on `jobs` threads. Output of each day is buffered and printed in the order of `days`.
+fn run_parallel(
+    solutions: &Registry,
+    year: Year,
+    days: &[Day],
+    options: &RunOptions,
+    jobs: usize,
+) -> Vec<Vec<PartRecord>> {
+    let next_index = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(days.len()) {
+            let sender = sender.clone();
+            let next_index = &next_index;
+
+            scope.spawn(move || loop {
+                let index = next_index.fetch_add(1, Ordering::Relaxed);
+                let Some(&day) = days.get(index) else {
+                    break;
+                };
+
+                let mut output = vec![];
+                let records = run_day(solutions, year, day, options, &mut output);
+                let _ = sender.send((index, output, records));
+            });
         }
 
-        thread.join().unwrap();
-        cmd.wait()?;
+        // drop the original sender so that the receiver terminates once all workers are done.
+        drop(sender);
 
-        Ok(output)
-    }
+        let mut pending = BTreeMap::new();
+        let mut results = vec![];
+        let mut stdout = stdout();
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timings {
-        let mut timings = super::Timings {
-            day,
-            part_1: None,
-            part_2: None,
-            total_nanos: 0_f64,
-        };
+        for (index, output, records) in receiver {
+            pending.insert(index, (output, records));
 
-        output
-            .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
-                }
+            while let Some((output, records)) = pending.remove(&results.len()) {
+                let _ = stdout.write_all(&output);
+                let _ = stdout.flush();
+                results.push(records);
+            }
+        }
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
+        results
+    })
+}
 
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
-                }
+/// Runs the solution for a given day and writes its section of the output to `out`.
+fn run_day(
+    solutions: &Registry,
+    year: Year,
+    day: Day,
+    options: &RunOptions,
+    out: &mut impl Write,
+) -> Vec<PartRecord> {
+    let is_human = options.format == OutputFormat::Human;
+
+    if is_human {
+        if day > 1 {
+            let _ = writeln!(out);
+        }
 
-                timings.total_nanos += nanos;
-            });
+        let _ = writeln!(out, "{ANSI_BOLD}Day {day}{ANSI_RESET}");
+        let _ = writeln!(out, "------");
+        let _ = out.flush();
+    }
 
-        timings
+    let records = run_solution(solutions, year, day, options, out);
+
+    if options.quiet {
+        for record in &records {
+            let _ = runner::write_record(out, record, options.format);
+        }
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+    if records.is_empty() && is_human {
+        let _ = writeln!(out, "Not solved.");
     }
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
+    records
+}
 
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
+fn find_solution(solutions: &Registry, year: Year, day: Day) -> Option<&'static dyn Solution> {
+    solutions
+        .iter()
+        .find(|x| x.year() == year && x.day() == day)
+        .copied()
+}
 
-        Some((str_timing, parsed_timing))
-    }
+/// Runs the solution for a given day, if it has been scaffolded and has an input file.
+fn run_solution(
+    solutions: &Registry,
+    year: Year,
+    day: Day,
+    options: &RunOptions,
+    out: &mut impl Write,
+) -> Vec<PartRecord> {
+    let Some(solution) = find_solution(solutions, year, day) else {
+        return vec![];
+    };
+
+    let input_path = get_path_for_input(year, day);
+    let is_missing =
+        !Path::new(&input_path).exists() && !encryption::get_encrypted_path(&input_path).exists();
+
+    let input = match encryption::read_to_string(&input_path) {
+        Ok(input) => input,
+        Err(e) => {
+            let message = if is_missing {
+                format!("Missing input file \"{input_path}\".")
+            } else {
+                format!("Could not read input file \"{input_path}\": {e}.")
+            };
+            // keep stdout machine-readable.
+            if options.format == OutputFormat::Human {
+                let _ = writeln!(out, "{message}");
+            } else {
+                eprintln!("{message}");
+            }
+            return vec![];
+        }
+    };
+    let records = solution.run(&input, options);
+    runner::save_last_run(year, day, &records, options);
+    records
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::get_timings;
+    use crate::day;
+    use crate::template::runner::PartRecord;
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
-    #[cfg(feature = "test_lib")]
     macro_rules! assert_approx_eq {
         ($a:expr, $b:expr) => {{
             let (a, b) = (&$a, &$b);
@@ -200,55 +380,82 @@ mod child_commands {
         }};
     }
 
-    #[cfg(feature = "test_lib")]
-    mod tests {
-        use super::parse_exec_time;
+    fn parse_records(lines: &[&str]) -> Vec<PartRecord> {
+        lines
+            .iter()
+            .filter_map(|line| PartRecord::from_json(line))
+            .collect()
+    }
 
-        use crate::day;
+    const STATS: &str = r#""stats":{"samples":5,"outliers":0,"mean_ns":1,"min_ns":1,"median_ns":1,"p95_ns":1,"std_dev_ns":0}"#;
+
+    #[test]
+    fn test_well_formed() {
+        let records = parse_records(&[
+            &format!(
+                r#"{{"day":1,"part":1,"answer":"0","duration_ns":74,"samples":5,"status":"solved",{STATS}}}"#
+            ),
+            &format!(
+                r#"{{"day":1,"part":2,"answer":"10","duration_ns":74130000,"samples":5,"status":"solved",{STATS}}}"#
+            ),
+        ]);
+        let res = get_timings(&records, day!(1), 2);
+        assert_approx_eq!(res.total_nanos, 74130074_f64);
+        assert_eq!(res.part_1.unwrap(), "74.0ns");
+        assert_eq!(res.part_2.unwrap(), "74.1ms");
+    }
 
-        #[test]
-        fn test_well_formed() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
-                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
-        }
+    #[test]
+    fn test_patterns_in_input() {
+        let records = parse_records(&[
+            "Submitting result via aoc-cli...",
+            &format!(
+                r#"{{"day":1,"part":1,"answer":"@ @ @ ( ) ms (2s @ 5 samples)","duration_ns":2000000000,"samples":5,"status":"solved",{STATS}}}"#
+            ),
+        ]);
+        let res = get_timings(&records, day!(1), 2);
+        assert_approx_eq!(res.total_nanos, 2000000000_f64);
+        assert_eq!(res.part_1.unwrap(), "2.0s");
+        assert_eq!(res.part_2.is_none(), true);
+    }
 
-        #[test]
-        fn test_patterns_in_input() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: @ @ @ ( ) ms (2s @ 5 samples)".into(),
-                    "Part 2: 10s (100ms @ 1 samples)".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
-        }
+    #[test]
+    fn test_untimed_parts() {
+        let records = parse_records(&[
+            r#"{"day":1,"part":1,"answer":"1","duration_ns":100,"samples":1,"status":"solved"}"#,
+            r#"{"day":1,"part":2,"answer":null,"duration_ns":100,"samples":1,"status":"unsolved"}"#,
+        ]);
+        assert_eq!(records.len(), 2);
+        let res = get_timings(&records, day!(1), 2);
+        assert_approx_eq!(res.total_nanos, 0_f64);
+        assert_eq!(res.part_1.is_none(), true);
+        assert_eq!(res.part_2.is_none(), true);
+    }
 
-        #[test]
-        fn test_missing_parts() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: ✖        ".into(),
-                    "Part 2: ✖        ".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 0_f64);
-            assert_eq!(res.part_1.is_none(), true);
-            assert_eq!(res.part_2.is_none(), true);
-        }
+    #[test]
+    fn test_parse_step() {
+        let records = parse_records(&[
+            &format!(
+                r#"{{"day":1,"part":0,"answer":null,"duration_ns":1000,"samples":5,"status":"parsed",{STATS}}}"#
+            ),
+            &format!(
+                r#"{{"day":1,"part":1,"answer":"1","duration_ns":2000,"samples":5,"status":"solved",{STATS}}}"#
+            ),
+        ]);
+        let res = get_timings(&records, day!(1), 2);
+        assert_approx_eq!(res.total_nanos, 3000_f64);
+        assert_eq!(res.parse.unwrap(), "1.0µs");
+        assert_eq!(res.part_1.unwrap(), "2.0µs");
+    }
+
+    #[test]
+    fn test_single_part() {
+        let records = parse_records(&[&format!(
+            r#"{{"day":25,"part":1,"answer":"1","duration_ns":2000,"samples":5,"status":"solved",{STATS}}}"#
+        )]);
+        let res = get_timings(&records, day!(25), 1);
+        assert_eq!(res.is_single_part, true);
+        assert_eq!(res.part_1.unwrap(), "2.0µs");
+        assert_eq!(res.part_2.is_none(), true);
     }
 }
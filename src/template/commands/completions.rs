@@ -0,0 +1,387 @@
+/// Generates completion scripts for the cargo aliases of the template.
+/// The scripts complete `cargo <alias>` with the flags of each command and the days of the year,
+/// and fall back to the completions of cargo itself for anything else. Only values of path flags complete file names.
+use std::fmt::{Display, Write};
+use std::str::FromStr;
+
+use crate::template::commands::help::{self, Arg, Flag, COMMANDS, GLOBAL_FLAGS};
+use crate::{all_days, Year};
+
+/// A shell that completion scripts can be generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = ShellFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            _ => Err(ShellFromStrError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a [`Shell`].
+#[derive(Debug)]
+pub struct ShellFromStrError;
+
+impl std::error::Error for ShellFromStrError {}
+
+impl Display for ShellFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a shell of either \"bash\", \"zsh\" or \"fish\"")
+    }
+}
+
+/// Prints the completion script for `shell`. Days are completed with the days of `year`.
+pub fn handle(shell: Shell, year: Year) {
+    print!("{}", generate(shell, year));
+}
+
+#[must_use]
+pub fn generate(shell: Shell, year: Year) -> String {
+    let days: Vec<String> = all_days(year)
+        .map(|day| day.into_inner().to_string())
+        .collect();
+
+    match shell {
+        Shell::Bash => generate_bash(&days),
+        Shell::Zsh => generate_zsh(&days),
+        Shell::Fish => generate_fish(&days),
+    }
+}
+
+fn get_choices(arg: Arg, days: &[String]) -> Vec<String> {
+    match arg {
+        Arg::Day => days.to_vec(),
+        Arg::Shell => vec!["bash".into(), "zsh".into(), "fish".into()],
+    }
+}
+
+/// A cargo alias to complete, with the argument and flags of the command it runs.
+struct Completion {
+    alias: &'static str,
+    about: &'static str,
+    arg: Option<Arg>,
+    flags: Vec<&'static Flag>,
+}
+
+/// A cargo alias that runs a command with some of its flags, see `.cargo/config.toml`.
+struct Shortcut {
+    alias: &'static str,
+    command: &'static str,
+    flags: &'static [&'static str],
+    about: &'static str,
+}
+
+const SHORTCUTS: &[Shortcut] = &[Shortcut {
+    alias: "time",
+    command: "all",
+    flags: &["--release", "--time"],
+    about: "Benchmark the solutions of all days, short for `cargo all --release --time`.",
+}];
+
+/// The commands and shortcuts to complete. Shortcuts do not complete the flags they already pass.
+fn get_completions() -> Vec<Completion> {
+    let commands = COMMANDS.iter().map(|command| Completion {
+        alias: command.alias,
+        about: command.about,
+        arg: command.arg,
+        flags: command.flags.iter().chain(GLOBAL_FLAGS).collect(),
+    });
+
+    let shortcuts = SHORTCUTS.iter().map(|shortcut| {
+        let command = help::find(shortcut.command).expect("shortcuts run a command");
+        Completion {
+            alias: shortcut.alias,
+            about: shortcut.about,
+            arg: command.arg,
+            flags: command
+                .flags
+                .iter()
+                .chain(GLOBAL_FLAGS)
+                .filter(|x| !shortcut.flags.contains(&x.name))
+                .collect(),
+        }
+    });
+
+    commands.chain(shortcuts).collect()
+}
+
+fn generate_bash(days: &[String]) -> String {
+    let completions = get_completions();
+    let aliases: Vec<&str> = completions.iter().map(|x| x.alias).collect();
+
+    let mut out = String::new();
+    out.push_str("_advent_of_code() {\n");
+    out.push_str("    local cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    out.push_str("    local prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+    out.push_str("    local words\n");
+    out.push_str("    COMPREPLY=()\n\n");
+    out.push_str("    if [[ $COMP_CWORD -eq 1 ]]; then\n");
+    out.push_str("        declare -F _cargo >/dev/null && _cargo \"$@\"\n");
+    let _ = writeln!(
+        out,
+        "        COMPREPLY+=($(compgen -W \"{}\" -- \"$cur\"))",
+        aliases.join(" ")
+    );
+    out.push_str("        return\n");
+    out.push_str("    fi\n\n");
+    out.push_str("    case \"${COMP_WORDS[1]}\" in\n");
+
+    for command in &completions {
+        let _ = writeln!(out, "        {})", command.alias);
+        out.push_str("            case \"$prev\" in\n");
+
+        for flag in command.flags.iter().filter(|x| x.value.is_some()) {
+            if flag.is_path {
+                let _ = writeln!(
+                    out,
+                    "                {}) COMPREPLY=($(compgen -f -- \"$cur\")); return ;;",
+                    flag.name
+                );
+            } else if flag.choices.is_empty() {
+                let _ = writeln!(out, "                {}) return ;;", flag.name);
+            } else {
+                let _ = writeln!(
+                    out,
+                    "                {}) words=\"{}\" ;;",
+                    flag.name,
+                    flag.choices.join(" ")
+                );
+            }
+        }
+
+        let mut words: Vec<String> = command
+            .arg
+            .map(|arg| get_choices(arg, days))
+            .unwrap_or_default();
+        words.extend(command.flags.iter().map(|x| x.name.to_string()));
+
+        let _ = writeln!(out, "                *) words=\"{}\" ;;", words.join(" "));
+        out.push_str("            esac\n");
+        out.push_str("            ;;\n");
+    }
+
+    out.push_str("        *)\n");
+    out.push_str("            declare -F _cargo >/dev/null && _cargo \"$@\"\n");
+    out.push_str("            return\n");
+    out.push_str("            ;;\n");
+    out.push_str("    esac\n\n");
+    out.push_str("    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))\n");
+    out.push_str("}\n\n");
+    out.push_str("complete -F _advent_of_code cargo\n");
+    out
+}
+
+/// Escapes `s` for use in a single-quoted `_arguments` or `_describe` spec.
+fn escape_zsh(s: &str) -> String {
+    s.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn generate_zsh(days: &[String]) -> String {
+    let completions = get_completions();
+    let mut out = String::new();
+    out.push_str("#compdef cargo\n\n");
+    out.push_str("_advent_of_code() {\n");
+    out.push_str("    if (( CURRENT == 2 )); then\n");
+    out.push_str("        local -a commands\n");
+    out.push_str("        commands=(\n");
+    for command in &completions {
+        let _ = writeln!(
+            out,
+            "            '{}:{}'",
+            command.alias,
+            escape_zsh(command.about)
+        );
+    }
+    out.push_str("        )\n");
+    out.push_str("        _describe 'command' commands\n");
+    out.push_str("        (( $+functions[_cargo] )) && _cargo\n");
+    out.push_str("        return\n");
+    out.push_str("    fi\n\n");
+    out.push_str("    case $words[2] in\n");
+
+    for command in &completions {
+        let _ = writeln!(out, "        {})", command.alias);
+        out.push_str("            shift words\n");
+        out.push_str("            (( CURRENT-- ))\n");
+        out.push_str("            _arguments");
+
+        if let Some(arg) = command.arg {
+            let name = arg_name(arg);
+            let _ = write!(
+                out,
+                " \\\n                '1:{name}:({})'",
+                get_choices(arg, days).join(" ")
+            );
+        }
+
+        for flag in &command.flags {
+            let about = escape_zsh(flag.about);
+            let _ = match flag.value {
+                None => write!(out, " \\\n                '{}[{about}]'", flag.name),
+                Some(value) => {
+                    let value = value.trim_matches(['<', '>']);
+                    let action = if flag.is_path {
+                        "_files".to_string()
+                    } else if flag.choices.is_empty() {
+                        // a space shows the value as a message without completing anything.
+                        " ".to_string()
+                    } else {
+                        format!("({})", flag.choices.join(" "))
+                    };
+                    write!(
+                        out,
+                        " \\\n                '{}[{about}]:{value}:{action}'",
+                        flag.name
+                    )
+                }
+            };
+        }
+
+        out.push_str("\n            ;;\n");
+    }
+
+    out.push_str("        *)\n");
+    out.push_str("            (( $+functions[_cargo] )) && _cargo\n");
+    out.push_str("            ;;\n");
+    out.push_str("    esac\n");
+    out.push_str("}\n\n");
+    out.push_str("compdef _advent_of_code cargo\n");
+    out
+}
+
+fn arg_name(arg: Arg) -> &'static str {
+    match arg {
+        Arg::Day => "day",
+        Arg::Shell => "shell",
+    }
+}
+
+/// Escapes `s` for use in a single-quoted fish string.
+fn escape_fish(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn generate_fish(days: &[String]) -> String {
+    let completions = get_completions();
+    let mut out = String::new();
+
+    for command in &completions {
+        let _ = writeln!(
+            out,
+            "complete -c cargo -n __fish_use_subcommand -f -a {} -d '{}'",
+            command.alias,
+            escape_fish(command.about)
+        );
+    }
+
+    for command in &completions {
+        let condition = format!("'__fish_seen_subcommand_from {}'", command.alias);
+
+        if let Some(arg) = command.arg {
+            let _ = writeln!(
+                out,
+                "complete -c cargo -n {condition} -f -a '{}'",
+                get_choices(arg, days).join(" ")
+            );
+        }
+
+        for flag in &command.flags {
+            let name = flag.name.trim_start_matches('-');
+            let about = escape_fish(flag.about);
+            let _ = match flag.value {
+                None => writeln!(
+                    out,
+                    "complete -c cargo -n {condition} -l {name} -d '{about}'"
+                ),
+                Some(_) if flag.is_path => writeln!(
+                    out,
+                    "complete -c cargo -n {condition} -l {name} -r -F -d '{about}'"
+                ),
+                Some(_) if flag.choices.is_empty() => writeln!(
+                    out,
+                    "complete -c cargo -n {condition} -l {name} -x -d '{about}'"
+                ),
+                Some(_) => writeln!(
+                    out,
+                    "complete -c cargo -n {condition} -l {name} -x -a '{}' -d '{about}'",
+                    flag.choices.join(" ")
+                ),
+            };
+        }
+    }
+
+    out
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{generate, Shell};
+    use crate::year;
+
+    #[test]
+    fn parses_shells() {
+        assert_eq!("zsh".parse::<Shell>().unwrap(), Shell::Zsh);
+        assert!("powershell".parse::<Shell>().is_err());
+    }
+
+    #[test]
+    fn completes_days_of_year() {
+        let script = generate(Shell::Bash, year!(2023));
+        assert!(script.contains(
+            "words=\"1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 --release"
+        ));
+
+        let script = generate(Shell::Fish, year!(2025));
+        assert!(script.contains("-a '1 2 3 4 5 6 7 8 9 10 11 12'\n"));
+    }
+
+    #[test]
+    fn completes_flag_values() {
+        let script = generate(Shell::Bash, year!(2023));
+        assert!(script.contains("--format) words=\"human json\" ;;"));
+
+        let script = generate(Shell::Zsh, year!(2023));
+        assert!(script.contains("'--submit[Submit the answer of a part via aoc-cli.]:part:(1 2)'"));
+
+        let script = generate(Shell::Fish, year!(2023));
+        assert!(script
+            .contains("complete -c cargo -n '__fish_seen_subcommand_from test-day' -l watch -d"));
+    }
+
+    #[test]
+    fn completes_files_only_for_paths() {
+        let script = generate(Shell::Bash, year!(2023));
+        assert!(script.contains("--input) COMPREPLY=($(compgen -f -- \"$cur\")); return ;;"));
+        assert!(script.contains("--year) return ;;"));
+        assert_eq!(script.matches("compgen -f").count(), 1);
+
+        let script = generate(Shell::Zsh, year!(2023));
+        assert!(script.contains(":path:_files'"));
+        assert!(script.contains("'--jobs[Number of days that run at the same time.]:n: '"));
+    }
+
+    #[test]
+    fn completes_shortcuts() {
+        let script = generate(Shell::Bash, year!(2023));
+        assert!(script.contains(" status completions time\""));
+        assert!(script.contains("        time)\n"));
+        assert!(script.contains("*) words=\"--budget --warmup"));
+
+        let script = generate(Shell::Fish, year!(2023));
+        assert!(script.contains("-f -a time -d"));
+        assert!(!script.contains("'__fish_seen_subcommand_from time' -l release"));
+    }
+}
@@ -1,15 +1,56 @@
-use crate::template::aoc_cli;
-use crate::Day;
-use std::process;
-
-pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+use std::{fs, path::Path};
+
+use crate::template::aoc_client::Client;
+use crate::template::commands::all::get_path_for_input;
+use crate::template::commands::read::get_path_for_puzzle;
+use crate::template::{encryption, markdown, Error};
+use crate::{Day, Year};
+
+/// Downloads the input and the puzzle description of `day` from the website, replacing existing files.
+/// The description is converted to Markdown, so that `cargo read` can print it offline.
+/// If a key is configured, only the encrypted input is written, see [`encryption`].
+pub fn handle(year: Year, day: Day) -> Result<(), Error> {
+    let client = Client::from_env()?;
+    let key = encryption::read_key().map_err(Error::io("could not read the input key"))?;
+
+    let input = client.get_input(year, day)?;
+    let puzzle = client.get_puzzle(year, day)?;
+
+    let input_path = get_path_for_input(year, day);
+    match key {
+        Some(key) => {
+            let encrypted_path = encryption::get_encrypted_path(&input_path);
+            write_file(&encrypted_path, key.encrypt(&input))?;
+            // don't leave a stale plaintext copy behind.
+            if Path::new(&input_path).exists() {
+                fs::remove_file(&input_path)
+                    .map_err(Error::io(format!("could not remove \"{input_path}\"")))?;
+            }
+            println!(
+                "🎄 Successfully wrote encrypted input to \"{}\".",
+                encrypted_path.display()
+            );
+        }
+        None => {
+            write_file(&input_path, &input)?;
+            println!("🎄 Successfully wrote input to \"{input_path}\".");
+        }
     }
 
-    if let Err(e) = aoc_cli::download(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
-    };
+    let puzzle_path = get_path_for_puzzle(year, day);
+    write_file(&puzzle_path, markdown::from_html(&puzzle))?;
+    println!("🎄 Successfully wrote puzzle to \"{puzzle_path}\".");
+
+    Ok(())
+}
+
+fn write_file(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<(), Error> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(Error::io(format!(
+            "could not create \"{}\"",
+            path.display()
+        )))?;
+    }
+    fs::write(path, contents).map_err(Error::io(format!("could not write \"{}\"", path.display())))
 }
@@ -0,0 +1,62 @@
+use std::fs;
+
+use crate::template::commands::read::get_path_for_puzzle;
+use crate::template::examples::{self, Expected};
+use crate::template::Error;
+use crate::{Day, Year};
+
+/// Extracts the examples of `day` and their expected answers from its stored puzzle description.
+/// Example files that already have contents and an existing manifest are kept, since they may have been edited by hand.
+pub fn handle(year: Year, day: Day) -> Result<(), Error> {
+    let puzzle_path = get_path_for_puzzle(year, day);
+    let puzzle = fs::read_to_string(&puzzle_path).map_err(Error::io(format!(
+        "could not read \"{puzzle_path}\", download the puzzle with `cargo download {day}`"
+    )))?;
+
+    let extracted = examples::extract(&puzzle);
+
+    if extracted.examples.is_empty() {
+        println!("No examples found in \"{puzzle_path}\".");
+        return Ok(());
+    }
+
+    let folder = format!("data/{year}/examples");
+    fs::create_dir_all(&folder).map_err(Error::io(format!("could not create \"{folder}\"")))?;
+
+    for (i, example) in extracted.examples.iter().enumerate() {
+        let path = examples::get_path(year, day, i + 1);
+        if fs::metadata(&path).is_ok_and(|x| x.len() > 0) {
+            println!("Kept existing example file \"{path}\"");
+            continue;
+        }
+        fs::write(&path, example).map_err(Error::io(format!("could not write \"{path}\"")))?;
+        println!("Created example file \"{path}\"");
+    }
+
+    let manifest_path = examples::get_manifest_path(year, day);
+    let manifest = match examples::read_manifest(year, day)? {
+        Some(manifest) => {
+            println!("Kept existing manifest \"{manifest_path}\"");
+            manifest
+        }
+        None => {
+            examples::write_manifest(year, day, &extracted.manifest)?;
+            println!("Created manifest \"{manifest_path}\"");
+            extracted.manifest
+        }
+    };
+
+    println!("---");
+    for (part, expected) in [("one", &manifest.part_one), ("two", &manifest.part_two)] {
+        match expected {
+            Some(Expected { example, answer }) => println!(
+                "🎄 Part {part} should return {answer} for \"{}\".",
+                examples::get_path(year, day, *example)
+            ),
+            None => println!("🎄 No expected answer found for part {part}."),
+        }
+    }
+    println!("🎄 The scaffolded tests check these answers, fix them in \"{manifest_path}\" if they are wrong.");
+
+    Ok(())
+}
@@ -0,0 +1,331 @@
+/// Usage text of the commands of the main binary. Shell completions are generated from the same descriptions.
+use std::fmt::Write;
+
+use crate::template::{Error, ANSI_BOLD, ANSI_RESET};
+
+/// A command of the main binary.
+pub struct Command {
+    /// Name of the subcommand, as passed to the main binary.
+    pub name: &'static str,
+    /// Name of the cargo alias that runs the command, see `.cargo/config.toml`.
+    pub alias: &'static str,
+    /// The free-standing argument of the command, if it takes one.
+    pub arg: Option<Arg>,
+    pub about: &'static str,
+    pub flags: &'static [Flag],
+}
+
+/// The free-standing argument of a command.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Arg {
+    Day,
+    Shell,
+}
+
+impl Arg {
+    fn placeholder(self) -> &'static str {
+        match self {
+            Self::Day => "<day>",
+            Self::Shell => "<shell>",
+        }
+    }
+}
+
+/// A flag of a command, optionally followed by a value.
+pub struct Flag {
+    pub name: &'static str,
+    /// Placeholder of the value, e.g. `<ms>`.
+    pub value: Option<&'static str>,
+    /// Possible values, completed by the shell.
+    pub choices: &'static [&'static str],
+    /// Whether the value is a path, which the shell completes with file names.
+    pub is_path: bool,
+    pub about: &'static str,
+}
+
+const fn flag(name: &'static str, about: &'static str) -> Flag {
+    Flag {
+        name,
+        value: None,
+        choices: &[],
+        is_path: false,
+        about,
+    }
+}
+
+const fn option(name: &'static str, value: &'static str, about: &'static str) -> Flag {
+    Flag {
+        name,
+        value: Some(value),
+        choices: &[],
+        is_path: false,
+        about,
+    }
+}
+
+const RELEASE: Flag = flag("--release", "Build with optimizations.");
+const TIME: Flag = flag("--time", "Benchmark each part.");
+const BUDGET: Flag = option("--budget", "<ms>", "Time spent benchmarking each part.");
+const WARMUP: Flag = option("--warmup", "<n>", "Untimed runs before benchmarking.");
+const FORMAT: Flag = Flag {
+    choices: &["human", "json"],
+    ..option("--format", "<format>", "Output format, `human` or `json`.")
+};
+const TIMEOUT: Flag = option(
+    "--timeout",
+    "<duration>",
+    "Stop parts running longer than this, e.g. `500ms` or `10s`.",
+);
+const WATCH: Flag = flag("--watch", "Re-run whenever the files of the day change.");
+
+/// Flags that are accepted by every command.
+pub const GLOBAL_FLAGS: &[Flag] = &[
+    option(
+        "--year",
+        "<year>",
+        "Year of advent, defaults to AOC_YEAR in .cargo/config.toml.",
+    ),
+    flag("--help", "Print help."),
+];
+
+/// All commands of the main binary.
+pub const COMMANDS: &[Command] = &[
+    Command {
+        name: "scaffold",
+        alias: "scaffold",
+        arg: Some(Arg::Day),
+        about: "Create the solution, input and example files of a day.",
+        flags: &[],
+    },
+    Command {
+        name: "download",
+        alias: "download",
+        arg: Some(Arg::Day),
+        about: "Download the input and puzzle description of a day.",
+        flags: &[],
+    },
+    Command {
+        name: "examples",
+        alias: "examples",
+        arg: Some(Arg::Day),
+        about: "Extract the examples and expected answers from the puzzle description of a day.",
+        flags: &[],
+    },
+    Command {
+        name: "read",
+        alias: "read",
+        arg: Some(Arg::Day),
+        about: "Print the downloaded puzzle description of a day.",
+        flags: &[],
+    },
+    Command {
+        name: "next",
+        alias: "next",
+        arg: None,
+        about: "Scaffold, download, extract examples and read the first day without a solution.",
+        flags: &[],
+    },
+    Command {
+        name: "solve",
+        alias: "solve",
+        arg: Some(Arg::Day),
+        about: "Run the solution of a day.",
+        flags: &[
+            RELEASE,
+            TIME,
+            BUDGET,
+            WARMUP,
+            Flag {
+                choices: &["1", "2"],
+                ..option(
+                    "--submit",
+                    "<part>",
+                    "Submit the answer of a part via aoc-cli.",
+                )
+            },
+            FORMAT,
+            flag("--example", "Run against the example instead of the input."),
+            Flag {
+                is_path: true,
+                ..option(
+                    "--input",
+                    "<path>",
+                    "Run against a file, or stdin with `-`.",
+                )
+            },
+            TIMEOUT,
+            WATCH,
+        ],
+    },
+    Command {
+        name: "test",
+        alias: "test-day",
+        arg: Some(Arg::Day),
+        about: "Run the unit tests of a day.",
+        flags: &[RELEASE, WATCH],
+    },
+    Command {
+        name: "all",
+        alias: "all",
+        arg: None,
+        about:
+            "Run the solutions of all days. `cargo time` is short for `cargo all --release --time`.",
+        flags: &[
+            RELEASE,
+            TIME,
+            BUDGET,
+            WARMUP,
+            FORMAT,
+            option("--jobs", "<n>", "Number of days that run at the same time."),
+            flag(
+                "--check",
+                "Fail if a part no longer returns its accepted answer.",
+            ),
+            TIMEOUT,
+            option(
+                "--save-baseline",
+                "<name>",
+                "Save the timings as a named baseline, requires --time.",
+            ),
+            option(
+                "--baseline",
+                "<name>",
+                "Compare the timings with a saved baseline, requires --time.",
+            ),
+        ],
+    },
+    Command {
+        name: "status",
+        alias: "status",
+        arg: None,
+        about: "Show the files, last answers and stars of every day.",
+        flags: &[],
+    },
+    Command {
+        name: "completions",
+        alias: "completions",
+        arg: Some(Arg::Shell),
+        about: "Print the completion script for a shell, `bash`, `zsh` or `fish`.",
+        flags: &[],
+    },
+];
+
+/// Looks up a command by its name or by its cargo alias.
+#[must_use]
+pub fn find(name: &str) -> Option<&'static Command> {
+    COMMANDS.iter().find(|x| x.name == name || x.alias == name)
+}
+
+/// Prints the help of `command`, or the list of all commands.
+pub fn handle(command: Option<&str>) -> Result<(), Error> {
+    match command {
+        None => print!("{}", format_overview()),
+        Some(name) => {
+            let command =
+                find(name).ok_or_else(|| Error::Usage(format!("Unknown command: {name}")))?;
+            print!("{}", format_command(command));
+        }
+    }
+    Ok(())
+}
+
+/// The usage line of `command`, e.g. `cargo solve <day> [options]`.
+#[must_use]
+pub fn format_usage(command: &Command) -> String {
+    let mut usage = format!("cargo {}", command.alias);
+    if let Some(arg) = command.arg {
+        usage.push(' ');
+        usage.push_str(arg.placeholder());
+    }
+    usage.push_str(" [options]");
+    usage
+}
+
+fn format_overview() -> String {
+    let rows: Vec<(String, &str)> = COMMANDS
+        .iter()
+        .map(|command| {
+            let name = match command.arg {
+                Some(arg) => format!("{} {}", command.alias, arg.placeholder()),
+                None => command.alias.to_string(),
+            };
+            (name, command.about)
+        })
+        .collect();
+
+    let mut out = format!("{ANSI_BOLD}Usage:{ANSI_RESET} cargo <command> [options]\n\n");
+    out.push_str(&format!("{ANSI_BOLD}Commands:{ANSI_RESET}\n"));
+    write_rows(&mut out, &rows);
+    out.push_str(&format!("\n{ANSI_BOLD}Options:{ANSI_RESET}\n"));
+    write_rows(&mut out, &flag_rows(GLOBAL_FLAGS));
+    out.push_str("\nRun `cargo <command> --help` for the options of a command.\n");
+    out
+}
+
+fn format_command(command: &Command) -> String {
+    let mut out = format!(
+        "{ANSI_BOLD}Usage:{ANSI_RESET} {}\n\n{}\n\n{ANSI_BOLD}Options:{ANSI_RESET}\n",
+        format_usage(command),
+        command.about
+    );
+    let flags: Vec<&Flag> = command.flags.iter().chain(GLOBAL_FLAGS).collect();
+    write_rows(&mut out, &flag_rows(flags));
+    out
+}
+
+fn flag_rows<'a>(flags: impl IntoIterator<Item = &'a Flag>) -> Vec<(String, &'a str)> {
+    flags
+        .into_iter()
+        .map(|flag| {
+            let name = match flag.value {
+                Some(value) => format!("{} {value}", flag.name),
+                None => flag.name.to_string(),
+            };
+            (name, flag.about)
+        })
+        .collect()
+}
+
+/// Writes `rows` as two aligned columns.
+fn write_rows(out: &mut String, rows: &[(String, &str)]) {
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, about) in rows {
+        let _ = writeln!(out, "  {name:<width$}  {about}");
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{find, format_command, format_overview, format_usage};
+
+    #[test]
+    fn finds_commands_by_alias() {
+        assert_eq!(find("test-day").map(|x| x.name), Some("test"));
+        assert_eq!(find("solve").map(|x| x.name), Some("solve"));
+        assert!(find("unknown").is_none());
+    }
+
+    #[test]
+    fn formats_usage() {
+        assert_eq!(
+            format_usage(find("solve").unwrap()),
+            "cargo solve <day> [options]"
+        );
+        assert_eq!(format_usage(find("all").unwrap()), "cargo all [options]");
+    }
+
+    #[test]
+    fn lists_flags_of_command() {
+        let help = format_command(find("solve").unwrap());
+        assert!(help.contains("  --release "));
+        assert!(help.contains("  --submit <part> "));
+        assert!(help.contains("  --year <year> "));
+    }
+
+    #[test]
+    fn lists_all_commands() {
+        let help = format_overview();
+        assert!(help.contains("  test-day <day> "));
+        assert!(help.contains("  completions <shell> "));
+    }
+}
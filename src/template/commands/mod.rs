@@ -1,5 +1,65 @@
+use std::env;
+use std::process::Command;
+
 pub mod all;
+pub mod completions;
 pub mod download;
+pub mod examples;
+pub mod help;
+pub mod next;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
+pub mod status;
+pub mod test;
+
+/// Variables that `cargo run` sets for the main binary, besides the `CARGO_PKG_*` ones.
+const CARGO_RUN_VARS: &[&str] = &[
+    "CARGO_MANIFEST_DIR",
+    "CARGO_MANIFEST_PATH",
+    "CARGO_CRATE_NAME",
+    "CARGO_BIN_NAME",
+    "CARGO_PRIMARY_PACKAGE",
+    "OUT_DIR",
+];
+
+/// Creates a `cargo` command that builds or runs the solutions.
+/// The variables that the outer `cargo run` set for the main binary are removed: build scripts of dependencies
+/// track some of them, so passing them on would rebuild those dependencies on every call.
+pub(crate) fn cargo_command() -> Command {
+    let mut cmd = Command::new("cargo");
+
+    for name in CARGO_RUN_VARS {
+        cmd.env_remove(name);
+    }
+    for (name, _) in env::vars_os() {
+        if name.to_string_lossy().starts_with("CARGO_PKG_") {
+            cmd.env_remove(name);
+        }
+    }
+
+    cmd
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::cargo_command;
+
+    #[test]
+    fn removes_variables_of_outer_cargo_run() {
+        let cmd = cargo_command();
+        let removed: Vec<String> = cmd
+            .get_envs()
+            .filter(|(_, value)| value.is_none())
+            .map(|(name, _)| name.to_string_lossy().into_owned())
+            .collect();
+
+        assert!(removed.iter().any(|x| x == "CARGO_MANIFEST_DIR"));
+        assert!(removed.iter().any(|x| x == "OUT_DIR"));
+        // set by `cargo test` as well.
+        assert!(removed.iter().any(|x| x == "CARGO_PKG_NAME"));
+        assert!(!removed
+            .iter()
+            .any(|x| x == "CARGO_HOME" || x == "CARGO_TARGET_DIR"));
+    }
+}
@@ -0,0 +1,96 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::template::commands::all::get_path_for_bin;
+use crate::template::commands::{download, examples, read, scaffold};
+use crate::template::{aoc_client, Error};
+use crate::{all_days, Day, Year};
+
+/// Downloads the input and puzzle of the first day of `year` that has no solution yet, scaffolds it, extracts its
+/// examples and prints the puzzle.
+pub fn handle(year: Year) -> Result<(), Error> {
+    let Some(day) = all_days(year).find(|&day| !Path::new(&get_path_for_bin(year, day)).exists())
+    else {
+        println!("🎄 All days of {year} are scaffolded.");
+        return Ok(());
+    };
+
+    aoc_client::read_session()?;
+
+    if let Ok(remaining) = get_unlock_time(year, day).duration_since(SystemTime::now()) {
+        return Err(Error::Usage(format!(
+            "Day {day} of {year} unlocks in {}.",
+            format_remaining(remaining)
+        )));
+    }
+
+    // download first, so that a failed download does not leave a scaffolded day behind that the next run would skip.
+    download::handle(year, day)?;
+    println!();
+    scaffold::handle(year, day)?;
+    println!();
+    examples::handle(year, day)?;
+    println!();
+    read::handle(year, day)
+}
+
+/// Puzzles unlock at midnight EST (UTC-5) on the day of december.
+fn get_unlock_time(year: Year, day: Day) -> SystemTime {
+    let days = days_since_epoch(year.into_inner().into(), 12, day.into_inner().into());
+    let seconds = days * 24 * 60 * 60 + 5 * 60 * 60;
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+/// Number of days between 1970-01-01 and the given date.
+/// See <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+fn days_since_epoch(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn format_remaining(remaining: Duration) -> String {
+    let minutes = remaining.as_secs().div_ceil(60);
+    match (minutes / (24 * 60), minutes / 60 % 24, minutes % 60) {
+        (0, 0, minutes) => format!("{minutes}m"),
+        (0, hours, minutes) => format!("{hours}h {minutes}m"),
+        (days, hours, _) => format!("{days}d {hours}h"),
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{days_since_epoch, format_remaining, get_unlock_time};
+    use crate::{day, year};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn counts_days_since_epoch() {
+        assert_eq!(days_since_epoch(1970, 1, 1), 0);
+        assert_eq!(days_since_epoch(2000, 3, 1), 11_017);
+        assert_eq!(days_since_epoch(2023, 12, 1), 19_692);
+    }
+
+    #[test]
+    fn unlocks_at_midnight_est() {
+        // 2023-12-01T05:00:00Z
+        let expected = UNIX_EPOCH + Duration::from_secs(1_701_406_800);
+        assert_eq!(get_unlock_time(year!(2023), day!(1)), expected);
+    }
+
+    #[test]
+    fn formats_remaining_time() {
+        assert_eq!(format_remaining(Duration::from_secs(30)), "1m");
+        assert_eq!(
+            format_remaining(Duration::from_secs(3 * 3600 + 120)),
+            "3h 2m"
+        );
+        assert_eq!(
+            format_remaining(Duration::from_secs(2 * 86400 + 3600)),
+            "2d 1h"
+        );
+    }
+}
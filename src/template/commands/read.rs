@@ -1,16 +1,20 @@
-use std::process;
+use std::fs;
 
-use crate::template::aoc_cli;
-use crate::Day;
+use crate::template::{markdown, Error};
+use crate::{Day, Year};
 
-pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
-    }
+/// Prints the stored puzzle description of `day`. This works offline, the description is stored by `cargo download`.
+pub fn handle(year: Year, day: Day) -> Result<(), Error> {
+    let path = get_path_for_puzzle(year, day);
+    let puzzle = fs::read_to_string(&path).map_err(Error::io(format!(
+        "could not read \"{path}\", download the puzzle with `cargo download {day}`"
+    )))?;
+    print!("{}", markdown::render(&puzzle));
+    Ok(())
+}
 
-    if let Err(e) = aoc_cli::read(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
-    };
+/// Path of the puzzle description of `day`, as Markdown.
+#[must_use]
+pub fn get_path_for_puzzle(year: Year, day: Day) -> String {
+    format!("data/{year}/puzzles/{day}.md")
 }
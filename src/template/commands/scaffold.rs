@@ -1,12 +1,14 @@
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::Write,
-    process,
+    path::Path,
 };
 
-use crate::Day;
+use crate::template::commands::all::{get_path_for_bin, get_path_for_input};
+use crate::template::{get_path, Error};
+use crate::{Day, Year};
 
-const MODULE_TEMPLATE: &str = r#"advent_of_code::solution!(DAY_NUMBER);
+const MODULE_TEMPLATE: &str = r#"advent_of_code::solution!(YEAR_NUMBER, DAY_NUMBER);
 
 pub fn part_one(input: &str) -> Option<u32> {
     None
@@ -22,14 +24,36 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let (example, expected) = advent_of_code::template::read_expected(YEAR, DAY, 1);
+        let result = part_one(&example);
+        assert_eq!(result.map(|x| x.to_string()), expected);
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let (example, expected) = advent_of_code::template::read_expected(YEAR, DAY, 2);
+        let result = part_two(&example);
+        assert_eq!(result.map(|x| x.to_string()), expected);
+    }
+}
+"#;
+
+/// Template for the last day of advent, which only has one puzzle part.
+const SINGLE_PART_MODULE_TEMPLATE: &str = r#"advent_of_code::solution!(YEAR_NUMBER, DAY_NUMBER, single_part);
+
+pub fn part_one(input: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let (example, expected) = advent_of_code::template::read_expected(YEAR, DAY, 1);
+        let result = part_one(&example);
+        assert_eq!(result.map(|x| x.to_string()), expected);
     }
 }
 "#;
@@ -39,56 +63,48 @@ fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
 }
 
 fn create_file(path: &str) -> Result<File, std::io::Error> {
+    if let Some(parent) = Path::new(path).parent() {
+        fs::create_dir_all(parent)?;
+    }
     OpenOptions::new().write(true).create(true).open(path)
 }
 
-pub fn handle(day: Day) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
-
-    let mut file = match safe_create_file(&module_path) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
-        }
-    };
+pub fn handle(year: Year, day: Day) -> Result<(), Error> {
+    let input_path = get_path_for_input(year, day);
+    let example_path = get_path("examples", year, day).display().to_string();
+    let module_path = get_path_for_bin(year, day);
+
+    let mut file = safe_create_file(&module_path).map_err(Error::io(format!(
+        "Failed to create module file \"{module_path}\""
+    )))?;
 
-    match file.write_all(
+    let template = if day == year.last_day() {
+        SINGLE_PART_MODULE_TEMPLATE
+    } else {
         MODULE_TEMPLATE
+    };
+
+    file.write_all(
+        template
+            .replace("YEAR_NUMBER", &year.to_string())
             .replace("DAY_NUMBER", &day.into_inner().to_string())
             .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
-        }
-    }
+    )
+    .map_err(Error::io("Failed to write module contents"))?;
+    println!("Created module file \"{}\"", &module_path);
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create input file: {e}");
-            process::exit(1);
-        }
-    }
+    create_file(&input_path).map_err(Error::io("Failed to create input file"))?;
+    println!("Created empty input file \"{}\"", &input_path);
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create example file: {e}");
-            process::exit(1);
-        }
-    }
+    create_file(&example_path).map_err(Error::io("Failed to create example file"))?;
+    println!("Created empty example file \"{}\"", &example_path);
 
     println!("---");
-    println!("🎄 Type `cargo solve {}` to run your solution.", day);
+    if Year::from_env() == Some(year) {
+        println!("🎄 Type `cargo solve {day}` to run your solution.");
+    } else {
+        println!("🎄 Type `cargo solve {day} --year {year}` to run your solution.");
+    }
+
+    Ok(())
 }
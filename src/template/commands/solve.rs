@@ -1,31 +1,82 @@
-use std::process::{Command, Stdio};
+use std::process::Stdio;
 
-use crate::Day;
+use crate::template::commands::all::get_path_for_bin;
+use crate::template::commands::cargo_command;
+use crate::template::runner::RunOptions;
+use crate::template::{alloc, encryption, get_bin_name, get_path, watch, Error, InputSource};
+use crate::{Day, Year};
 
-pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+/// Runs the binary of `day`. With `watch`, it is rebuilt and re-run whenever its source or input changes.
+/// The binary is built first, so that build failures can be told apart from failures of the solution.
+pub fn handle(
+    year: Year,
+    day: Day,
+    release: bool,
+    options: &RunOptions,
+    input: &InputSource,
+    watch: bool,
+) -> Result<(), Error> {
+    let mut cargo_args = vec!["--bin".to_string(), get_bin_name(year, day)];
 
     if release {
-        cmd_args.push("--release".to_string());
+        cargo_args.push("--release".to_string());
     }
 
+    // mirror the `alloc_stats` feature to the solution binary.
+    if alloc::is_enabled() {
+        cargo_args.push("--features".to_string());
+        cargo_args.push("alloc_stats".to_string());
+    }
+
+    let mut cmd_args = vec!["run".to_string()];
+    cmd_args.extend_from_slice(&cargo_args);
     cmd_args.push("--".to_string());
+    cmd_args.append(&mut options.to_args());
+    cmd_args.append(&mut input.to_args());
+
+    let command = || {
+        let mut cmd = cargo_command();
+        cmd.args(&cmd_args)
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit());
+        cmd
+    };
 
-    if let Some(submit_part) = submit_part {
-        cmd_args.push("--submit".to_string());
-        cmd_args.push(submit_part.to_string());
+    if !watch {
+        let build = cargo_command()
+            .arg("build")
+            .args(&cargo_args)
+            .status()
+            .map_err(Error::io("Failed to run cargo"))?;
+
+        if !build.success() {
+            return Err(Error::Build);
+        }
+
+        let status = command()
+            .status()
+            .map_err(Error::io("Failed to run cargo"))?;
+        return Error::from_status(status);
     }
 
-    if time {
-        cmd_args.push("--time".to_string());
+    if options.submit.is_some() {
+        return Err(Error::Usage(
+            "--submit can not be used together with --watch.".into(),
+        ));
     }
 
-    let mut cmd = Command::new("cargo")
-        .args(&cmd_args)
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .spawn()
-        .unwrap();
+    let Some(input_path) = input.path(year, day) else {
+        return Err(Error::Usage(
+            "--watch can not be used when reading the input from stdin.".into(),
+        ));
+    };
+
+    let mut paths = vec![
+        get_path_for_bin(year, day).into(),
+        encryption::get_stored_path(input_path),
+        get_path("examples", year, day),
+    ];
+    paths.dedup();
 
-    cmd.wait().unwrap();
+    watch::watch(&paths, command);
 }
@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::Path;
+
+use crate::template::commands::all::{get_path_for_bin, get_path_for_input};
+use crate::template::commands::read::get_path_for_puzzle;
+use crate::template::runner::{PartRecord, Status};
+use crate::template::stars::{self, Stars};
+use crate::template::{encryption, get_path, last_run, ANSI_BOLD, ANSI_RESET};
+use crate::{all_days, Day, Year};
+
+const HEADERS: [&str; 8] = [
+    "Day", "Bin", "Input", "Example", "Puzzle", "Part 1", "Part 2", "Stars",
+];
+
+/// Prints a table with the state of every day of `year`: which files exist, the answers and timings
+/// of the last run against the puzzle input, and which stars were earned.
+pub fn handle(year: Year) {
+    let stars: Vec<(Day, Stars)> = all_days(year)
+        .map(|day| {
+            let stars = stars::read(year, day).unwrap_or_else(|e| {
+                eprintln!("Failed to read stars of day {day}: {e}");
+                Stars::default()
+            });
+            (day, stars)
+        })
+        .collect();
+
+    let rows: Vec<[String; 8]> = stars
+        .iter()
+        .map(|&(day, stars)| get_row(year, day, stars))
+        .collect();
+
+    let widths: Vec<usize> = (0..HEADERS.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([HEADERS[i].len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    println!("{ANSI_BOLD}{}{ANSI_RESET}", format_row(&HEADERS, &widths));
+
+    for row in &rows {
+        println!("{}", format_row(row, &widths));
+    }
+
+    let count: usize = stars.iter().map(|(_, stars)| stars.count()).sum();
+    println!(
+        "\n{ANSI_BOLD}Stars:{ANSI_RESET} {count}/{}",
+        stars.len() * 2
+    );
+}
+
+fn get_row(year: Year, day: Day, stars: Stars) -> [String; 8] {
+    let records = last_run::read(year, day).unwrap_or_else(|e| {
+        eprintln!("Failed to read \"{}\": {e}", last_run::get_path(year, day));
+        vec![]
+    });
+
+    let find_part = |part: u8| records.iter().find(|x| x.part == part);
+
+    [
+        day.to_string(),
+        format_check(Path::new(&get_path_for_bin(year, day)).exists()),
+        format_check(has_input(year, day)),
+        format_check(has_contents(get_path("examples", year, day))),
+        format_check(has_contents(get_path_for_puzzle(year, day))),
+        format_part(find_part(1)),
+        format_part(find_part(2)),
+        stars.to_string(),
+    ]
+}
+
+/// Whether the plaintext or the encrypted input of `day` was downloaded.
+fn has_input(year: Year, day: Day) -> bool {
+    let path = get_path_for_input(year, day);
+    has_contents(&path) || has_contents(encryption::get_encrypted_path(&path))
+}
+
+/// Whether a file exists and is not empty. Scaffolding creates empty input and example files.
+fn has_contents(path: impl AsRef<Path>) -> bool {
+    fs::metadata(path).is_ok_and(|x| x.len() > 0)
+}
+
+fn format_check(value: bool) -> String {
+    if value { "✔" } else { "·" }.to_string()
+}
+
+/// Formats the answer and timing of a part, or its failure.
+fn format_part(record: Option<&PartRecord>) -> String {
+    let Some(record) = record else {
+        return "·".to_string();
+    };
+
+    let result = match (&record.status, &record.answer) {
+        (Status::TimedOut, _) => "timed out".to_string(),
+        (Status::Panicked, _) => "panicked".to_string(),
+        (_, None) => "unsolved".to_string(),
+        (Status::Wrong, Some(answer)) => format!("{answer} ✘"),
+        (_, Some(answer)) => answer.to_string(),
+    };
+
+    format!("{result} ({:.1?})", record.duration())
+}
+
+fn format_row<T: AsRef<str>>(cells: &[T], widths: &[usize]) -> String {
+    cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, &width)| format!("{:<width$}", cell.as_ref()))
+        .collect::<Vec<_>>()
+        .join("  ")
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_part, format_row};
+    use crate::template::runner::PartRecord;
+
+    fn record(json: &str) -> PartRecord {
+        PartRecord::from_json(json).unwrap()
+    }
+
+    #[test]
+    fn formats_parts() {
+        assert_eq!(format_part(None), "·");
+
+        let solved = record(
+            r#"{"day":1,"part":1,"answer":"42","duration_ns":1500,"samples":1,"status":"solved"}"#,
+        );
+        assert_eq!(format_part(Some(&solved)), "42 (1.5µs)");
+
+        let wrong = record(
+            r#"{"day":1,"part":1,"answer":"41","duration_ns":1500,"samples":1,"status":"wrong","expected":"42"}"#,
+        );
+        assert_eq!(format_part(Some(&wrong)), "41 ✘ (1.5µs)");
+
+        let timed_out = record(
+            r#"{"day":1,"part":2,"answer":null,"duration_ns":2000000000,"samples":0,"status":"timed_out"}"#,
+        );
+        assert_eq!(format_part(Some(&timed_out)), "timed out (2.0s)");
+    }
+
+    #[test]
+    fn pads_cells() {
+        assert_eq!(format_row(&["01", "✔", ""], &[3, 5, 2]), "01   ✔");
+    }
+}
@@ -0,0 +1,52 @@
+use std::process::Stdio;
+
+use crate::template::commands::all::get_path_for_bin;
+use crate::template::commands::cargo_command;
+use crate::template::{get_bin_name, get_path, watch, Error};
+use crate::{Day, Year};
+
+/// Runs the unit tests of `day`. With `watch`, they are re-run whenever its source or example changes.
+/// The tests are built first, so that build failures can be told apart from failing tests.
+pub fn handle(year: Year, day: Day, release: bool, watch: bool) -> Result<(), Error> {
+    let mut cmd_args = vec![
+        "test".to_string(),
+        "--bin".to_string(),
+        get_bin_name(year, day),
+    ];
+
+    if release {
+        cmd_args.push("--release".to_string());
+    }
+
+    let command = || {
+        let mut cmd = cargo_command();
+        cmd.args(&cmd_args)
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit());
+        cmd
+    };
+
+    if watch {
+        watch::watch(
+            &[
+                get_path_for_bin(year, day).into(),
+                get_path("examples", year, day),
+            ],
+            command,
+        );
+    }
+
+    let build = command()
+        .arg("--no-run")
+        .status()
+        .map_err(Error::io("Failed to run cargo"))?;
+
+    if !build.success() {
+        return Err(Error::Build);
+    }
+
+    let status = command()
+        .status()
+        .map_err(Error::io("Failed to run cargo"))?;
+    Error::from_status(status)
+}
@@ -0,0 +1,177 @@
+/// Module that encrypts puzzle inputs, so that they can be committed without publishing them.
+///
+/// When a key is configured, `cargo download` writes `data/<year>/inputs/<day>.txt.enc` instead of the plaintext
+/// input, and [`read_to_string`] decrypts it whenever the input is read. The key is 32 bytes written as 64 hex
+/// characters, read from `AOC_INPUT_KEY` or else from `.aoc_input_key` in the root of the repository.
+///
+/// An encrypted file is a random 24-byte nonce followed by the input sealed with XChaCha20-Poly1305.
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+
+/// Path of the key file, relative to the root of the repository. It is ignored by git.
+pub const KEY_PATH: &str = ".aoc_input_key";
+/// Environment variable that takes precedence over the key file, e.g. for CI.
+pub const KEY_ENV: &str = "AOC_INPUT_KEY";
+
+const NONCE_LEN: usize = 24;
+
+/// A key that encrypts and decrypts inputs.
+pub struct Key(chacha20poly1305::Key);
+
+impl Key {
+    /// Parses a key written as 64 hex characters. Surrounding whitespace is ignored.
+    pub fn parse(s: &str) -> Result<Self, io::Error> {
+        let s = s.trim();
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the key must be 64 hex characters",
+            )
+        };
+
+        if s.len() != 64 || !s.is_ascii() {
+            return Err(invalid());
+        }
+
+        let mut key = chacha20poly1305::Key::default();
+        for (byte, i) in key.iter_mut().zip((0..s.len()).step_by(2)) {
+            *byte = u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| invalid())?;
+        }
+        Ok(Self(key))
+    }
+
+    #[must_use]
+    pub fn encrypt(&self, plaintext: &str) -> Vec<u8> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = XChaCha20Poly1305::new(&self.0)
+            .encrypt(&nonce, plaintext.as_bytes())
+            .expect("encrypting in memory does not fail");
+
+        let mut data = nonce.to_vec();
+        data.extend(ciphertext);
+        data
+    }
+
+    /// Decrypts `data`. Returns [`None`] if it was encrypted with another key or is damaged.
+    #[must_use]
+    pub fn decrypt(&self, data: &[u8]) -> Option<String> {
+        if data.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = XChaCha20Poly1305::new(&self.0)
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .ok()?;
+        String::from_utf8(plaintext).ok()
+    }
+}
+
+/// Reads the configured key. Returns [`None`] if inputs are not encrypted.
+pub fn read_key() -> Result<Option<Key>, io::Error> {
+    let contents = match env::var(KEY_ENV) {
+        Ok(value) => value,
+        Err(_) => match fs::read_to_string(KEY_PATH) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        },
+    };
+
+    Key::parse(&contents).map(Some)
+}
+
+/// Path of the encrypted counterpart of `path`, e.g. `01.txt.enc` for `01.txt`.
+#[must_use]
+pub fn get_encrypted_path(path: impl AsRef<Path>) -> PathBuf {
+    let mut path = path.as_ref().as_os_str().to_owned();
+    path.push(".enc");
+    PathBuf::from(path)
+}
+
+/// Path of the file that stores the contents of `path`: its encrypted counterpart if there is one, else `path` itself.
+#[must_use]
+pub fn get_stored_path(path: impl AsRef<Path>) -> PathBuf {
+    let encrypted_path = get_encrypted_path(&path);
+    if encrypted_path.exists() {
+        encrypted_path
+    } else {
+        path.as_ref().to_path_buf()
+    }
+}
+
+/// Reads the file at `path`, or decrypts its encrypted counterpart if there is one.
+pub fn read_to_string(path: impl AsRef<Path>) -> Result<String, io::Error> {
+    let encrypted_path = get_encrypted_path(&path);
+
+    if !encrypted_path.exists() {
+        return fs::read_to_string(path);
+    }
+
+    let key = read_key()?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "\"{}\" is encrypted, but no key is set in {KEY_ENV} or \"{KEY_PATH}\"",
+                encrypted_path.display()
+            ),
+        )
+    })?;
+
+    key.decrypt(&fs::read(&encrypted_path)?).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "could not decrypt \"{}\", the key is wrong or the file is damaged",
+                encrypted_path.display()
+            ),
+        )
+    })
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{get_encrypted_path, Key};
+    use std::path::PathBuf;
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    #[test]
+    fn roundtrips_input() {
+        let key = Key::parse(KEY).unwrap();
+        let data = key.encrypt("1abc2\n");
+
+        assert!(!data.windows(5).any(|x| x == b"1abc2"));
+        assert_ne!(data, key.encrypt("1abc2\n"));
+        assert_eq!(key.decrypt(&data).as_deref(), Some("1abc2\n"));
+    }
+
+    #[test]
+    fn rejects_wrong_key_and_damaged_data() {
+        let key = Key::parse(KEY).unwrap();
+        let other = Key::parse(&KEY.replace("00", "ff")).unwrap();
+        let mut data = key.encrypt("1abc2\n");
+
+        assert_eq!(other.decrypt(&data), None);
+        data[30] ^= 1;
+        assert_eq!(key.decrypt(&data), None);
+        assert_eq!(key.decrypt(&[]), None);
+    }
+
+    #[test]
+    fn parses_hex_keys() {
+        assert!(Key::parse(&format!("{KEY}\n")).is_ok());
+        assert!(Key::parse(&KEY[2..]).is_err());
+        assert!(Key::parse(&KEY.replace("0a", "zz")).is_err());
+        assert_eq!(
+            get_encrypted_path("data/2023/inputs/01.txt"),
+            PathBuf::from("data/2023/inputs/01.txt.enc")
+        );
+    }
+}
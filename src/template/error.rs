@@ -0,0 +1,194 @@
+/// The error type of the template. Each kind of failure exits with its own code, so that scripts can tell them apart.
+///
+/// | Code | Failure                                  |
+/// | :--: | ---------------------------------------- |
+/// | 1    | invalid arguments or unparsable files    |
+/// | 2    | aoc-cli is not installed                 |
+/// | 3    | reading or writing a file failed         |
+/// | 4    | a solution does not build                |
+/// | 5    | a part no longer returns its answer      |
+/// | 6    | a part exceeded the timeout              |
+/// | 7    | a part panicked                          |
+/// | 8    | the session is missing or expired        |
+/// | 9    | the puzzle is not unlocked yet           |
+/// | 10   | the website limits the request rate      |
+/// | 11   | the website could not be reached         |
+use std::{
+    fmt::Display,
+    io,
+    process::{self, ExitStatus},
+    time::Duration,
+};
+
+use super::aoc_cli::AocCommandError;
+use crate::{Day, Year};
+
+#[derive(Debug)]
+pub enum Error {
+    /// Invalid command-line arguments.
+    Usage(String),
+    /// A file of the template could not be parsed.
+    Parser(String),
+    /// The `aoc` command is not installed or can not be called.
+    AocCliMissing,
+    /// aoc-cli was called but exited with a non-zero status.
+    AocCli,
+    /// Reading or writing a file failed.
+    Io { context: String, source: io::Error },
+    /// `cargo build` of a solution failed.
+    Build,
+    /// The number of parts that did not return their accepted answer.
+    WrongAnswer(usize),
+    /// The number of parts that exceeded the timeout.
+    TimedOut(usize),
+    /// The number of parts that panicked.
+    Panicked(usize),
+    /// No session cookie for the website is configured.
+    SessionMissing,
+    /// The website rejected the session cookie.
+    SessionExpired,
+    /// The puzzle of the day is not unlocked yet.
+    NotUnlocked(Year, Day),
+    /// The website limits the request rate, optionally with the time to wait.
+    RateLimited(Option<Duration>),
+    /// The website could not be reached or responded with an unexpected status.
+    Http(String),
+    /// A child process exited with a non-zero code after reporting its error itself.
+    Exit(i32),
+}
+
+impl Error {
+    /// Returns a function that wraps an [`io::Error`], for use with [`Result::map_err`].
+    pub fn io(context: impl Display) -> impl FnOnce(io::Error) -> Self {
+        move |source| Self::Io {
+            context: context.to_string(),
+            source,
+        }
+    }
+
+    /// Passes on the exit code of a child process that already reported its failure.
+    pub fn from_status(status: ExitStatus) -> Result<(), Self> {
+        match status.code() {
+            Some(0) => Ok(()),
+            code => Err(Self::Exit(code.unwrap_or(1))),
+        }
+    }
+
+    /// The exit code of the process for this error.
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Usage(_) | Self::Parser(_) | Self::AocCli => 1,
+            Self::AocCliMissing => 2,
+            Self::Io { .. } => 3,
+            Self::Build => 4,
+            Self::WrongAnswer(_) => 5,
+            Self::TimedOut(_) => 6,
+            Self::Panicked(_) => 7,
+            Self::SessionMissing | Self::SessionExpired => 8,
+            Self::NotUnlocked(..) => 9,
+            Self::RateLimited(_) => 10,
+            Self::Http(_) => 11,
+            Self::Exit(code) => *code,
+        }
+    }
+
+    /// Prints the error, unless it was already reported, and exits the process with its [exit code](Error::exit_code).
+    pub fn exit(self) -> ! {
+        if !matches!(self, Self::Exit(_)) {
+            eprintln!("Error: {self}");
+        }
+        process::exit(self.exit_code());
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Usage(message) | Self::Parser(message) => f.write_str(message),
+            Self::AocCliMissing => f.write_str(
+                "command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.",
+            ),
+            Self::AocCli => f.write_str("aoc-cli exited with a non-zero status."),
+            Self::Io { context, source } => write!(f, "{context}: {source}"),
+            Self::Build => f.write_str("the solution could not be built."),
+            Self::WrongAnswer(count) => write!(f, "{count} part(s) returned a wrong answer."),
+            Self::TimedOut(count) => write!(f, "{count} part(s) timed out."),
+            Self::Panicked(count) => write!(f, "{count} part(s) panicked."),
+            Self::SessionMissing => f.write_str(
+                "no session found. Set AOC_SESSION or paste your session cookie into ~/.adventofcode.session.",
+            ),
+            Self::SessionExpired => f.write_str(
+                "the session was rejected by the website. Log in again and update your session cookie.",
+            ),
+            Self::NotUnlocked(year, day) => write!(f, "day {day} of {year} is not unlocked yet."),
+            Self::RateLimited(Some(wait)) => write!(
+                f,
+                "too many requests, wait {}s before trying again.",
+                wait.as_secs()
+            ),
+            Self::RateLimited(None) => f.write_str("too many requests, wait before trying again."),
+            Self::Http(message) => f.write_str(message),
+            Self::Exit(code) => write!(f, "exited with status {code}."),
+        }
+    }
+}
+
+impl From<AocCommandError> for Error {
+    fn from(e: AocCommandError) -> Self {
+        match e {
+            AocCommandError::CommandNotFound | AocCommandError::CommandNotCallable => {
+                Self::AocCliMissing
+            }
+            AocCommandError::BadExitStatus(_) => Self::AocCli,
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Error;
+    use crate::{day, year};
+    use std::io;
+
+    #[test]
+    fn distinct_exit_codes() {
+        let errors = [
+            Error::AocCliMissing,
+            Error::io("could not read \"x\"")(io::Error::from(io::ErrorKind::NotFound)),
+            Error::Build,
+            Error::WrongAnswer(1),
+            Error::TimedOut(1),
+            Error::Panicked(1),
+            Error::SessionExpired,
+            Error::NotUnlocked(year!(2023), day!(1)),
+            Error::RateLimited(None),
+            Error::Http(String::new()),
+        ];
+
+        let mut codes: Vec<i32> = errors.iter().map(Error::exit_code).collect();
+        codes.dedup();
+        assert_eq!(codes, vec![2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+        assert_eq!(Error::Usage(String::new()).exit_code(), 1);
+    }
+
+    #[test]
+    fn passes_through_child_exit_codes() {
+        assert_eq!(Error::Exit(101).exit_code(), 101);
+    }
+
+    #[test]
+    fn formats_io_errors_with_context() {
+        let error = Error::io("could not read \"x\"")(io::Error::other("oops"));
+        assert_eq!(error.to_string(), "could not read \"x\": oops");
+    }
+}
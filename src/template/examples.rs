@@ -0,0 +1,359 @@
+/// Module that extracts the examples of a puzzle and their expected answers from its Markdown description.
+///
+/// Examples are written to `data/<year>/examples/<day>.txt`, further examples to `<day>-2.txt`, `<day>-3.txt` and so on.
+/// The expected answer of each part is stored in a manifest next to them, `data/<year>/examples/<day>.toml`:
+///
+/// ```toml
+/// [part_one]
+/// example = 1
+/// answer = "142"
+///
+/// [part_two]
+/// example = 2
+/// answer = "281"
+/// ```
+///
+/// Puzzle descriptions are prose, so extraction is a best guess: the first code block and every code block that is
+/// introduced with "For example, ...:" or "...example:" count as examples. The expected answer of a part is the last
+/// highlighted code in its description, e.g. *`142`*, and belongs to the example above it.
+/// Check the manifest and fix it by hand where the guess is wrong.
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::template::Error;
+use crate::{Day, Year};
+
+/// The examples and expected answers found in a puzzle description.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Extracted {
+    /// Contents of each distinct example, in order of appearance.
+    pub examples: Vec<String>,
+    pub manifest: Manifest,
+}
+
+/// The expected answers of the examples of a day.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part_one: Option<Expected>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part_two: Option<Expected>,
+}
+
+/// The expected answer of a part for one of the examples.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Expected {
+    /// Number of the example, starting at 1.
+    pub example: usize,
+    pub answer: String,
+}
+
+impl Manifest {
+    /// Returns the expected answer for `part`, if there is one.
+    #[must_use]
+    pub fn get(&self, part: u8) -> Option<&Expected> {
+        match part {
+            1 => self.part_one.as_ref(),
+            2 => self.part_two.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Parses a manifest from the contents of a manifest file.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        toml::from_str(s).map_err(|e| Error::Parser(format!("could not parse manifest: {e}")))
+    }
+}
+
+/// Path of example `number` of `day`. The first example has no suffix, so that it is the file read by `--example`.
+#[must_use]
+pub fn get_path(year: Year, day: Day, number: usize) -> String {
+    match number {
+        1 => format!("data/{year}/examples/{day}.txt"),
+        number => format!("data/{year}/examples/{day}-{number}.txt"),
+    }
+}
+
+#[must_use]
+pub fn get_manifest_path(year: Year, day: Day) -> String {
+    format!("data/{year}/examples/{day}.toml")
+}
+
+/// Reads the manifest of `day`. Returns [`None`] if no examples were extracted.
+pub fn read_manifest(year: Year, day: Day) -> Result<Option<Manifest>, Error> {
+    let path = get_manifest_path(year, day);
+
+    if !Path::new(&path).exists() {
+        return Ok(None);
+    }
+
+    let contents =
+        fs::read_to_string(&path).map_err(Error::io(format!("could not read \"{path}\"")))?;
+    Manifest::parse(&contents).map(Some)
+}
+
+/// Replaces the manifest of `day`.
+pub fn write_manifest(year: Year, day: Day, manifest: &Manifest) -> Result<(), Error> {
+    let path = get_manifest_path(year, day);
+    let contents = toml::to_string(manifest)
+        .map_err(|e| Error::Parser(format!("could not serialize manifest: {e}")))?;
+
+    fs::write(&path, contents).map_err(Error::io(format!("could not write \"{path}\"")))
+}
+
+enum Block {
+    Heading(String),
+    Paragraph(String),
+    Code(String),
+}
+
+/// Finds the examples and expected answers in the puzzle description `markdown`.
+#[must_use]
+pub fn extract(markdown: &str) -> Extracted {
+    let blocks = parse_blocks(markdown);
+
+    let mut extracted = Extracted::default();
+    let mut part = 1;
+    // index of the example above the current block.
+    let mut current: Option<usize> = None;
+
+    for (i, block) in blocks.iter().enumerate() {
+        match block {
+            Block::Heading(text) => {
+                if text.contains("Part Two") {
+                    part = 2;
+                }
+            }
+            Block::Code(code) => {
+                let is_first = !blocks[..i].iter().any(|x| matches!(x, Block::Code(_)));
+                let is_introduced = i > 0 && introduces_example(&blocks[i - 1]);
+                if is_first || is_introduced {
+                    current = Some(add_example(&mut extracted.examples, code));
+                }
+            }
+            Block::Paragraph(text) => {
+                let Some(answer) = find_highlighted_code(text).pop() else {
+                    continue;
+                };
+
+                // the answer can be given in the paragraph that introduces its example.
+                let example = match blocks.get(i + 1) {
+                    Some(Block::Code(code)) if introduces_example(block) => {
+                        Some(add_example(&mut extracted.examples, code))
+                    }
+                    _ => current,
+                };
+
+                let Some(example) = example else {
+                    continue;
+                };
+
+                let expected = Some(Expected {
+                    example: example + 1,
+                    answer,
+                });
+
+                if part == 1 {
+                    extracted.manifest.part_one = expected;
+                } else {
+                    extracted.manifest.part_two = expected;
+                }
+            }
+        }
+    }
+
+    extracted
+}
+
+/// Adds `code` to `examples` unless it is already there, and returns its index.
+fn add_example(examples: &mut Vec<String>, code: &str) -> usize {
+    match examples.iter().position(|x| x == code) {
+        Some(index) => index,
+        None => {
+            examples.push(code.to_string());
+            examples.len() - 1
+        }
+    }
+}
+
+fn introduces_example(block: &Block) -> bool {
+    let Block::Paragraph(text) = block else {
+        return false;
+    };
+    let text = text.trim().to_lowercase();
+    let last_sentence = text.rsplit(['.', '!', '?']).next().unwrap_or_default();
+    text.ends_with("example:")
+        || (text.ends_with(':') && last_sentence.trim().starts_with("for example"))
+}
+
+/// Splits `markdown` into headings, paragraphs and code blocks. List items are treated as paragraphs.
+fn parse_blocks(markdown: &str) -> Vec<Block> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut blocks = vec![];
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if line.trim_start().starts_with("```") {
+            let mut code = vec![];
+            while i < lines.len() && !lines[i].trim_start().starts_with("```") {
+                code.push(lines[i]);
+                i += 1;
+            }
+            i += 1;
+
+            // aoc-cli leaves an empty line at the end of code blocks.
+            while code.last().is_some_and(|x| x.is_empty()) {
+                code.pop();
+            }
+            blocks.push(Block::Code(code.join("\n") + "\n"));
+            continue;
+        }
+
+        if line.starts_with('#') {
+            blocks.push(Block::Heading(line.trim_start_matches('#').trim().into()));
+            continue;
+        }
+
+        // setext headings, as written by aoc-cli.
+        if lines
+            .get(i)
+            .is_some_and(|x| x.len() >= 3 && x.chars().all(|c| c == '-'))
+        {
+            i += 1;
+            blocks.push(Block::Heading(line.trim().into()));
+            continue;
+        }
+
+        blocks.push(Block::Paragraph(line.trim().into()));
+    }
+
+    blocks
+}
+
+/// Finds highlighted code in `text`, written as *`142`*, or as `*142*` by aoc-cli.
+fn find_highlighted_code(text: &str) -> Vec<String> {
+    let mut found = vec![];
+    let mut rest = text;
+
+    while let Some(start) = rest.find(['*', '`']) {
+        let is_escaped = rest[..start].ends_with('\\');
+        let (open, close) = match &rest[start..] {
+            x if x.starts_with("*`") => ("*`", "`*"),
+            x if x.starts_with("`*") => ("`*", "*`"),
+            _ => ("", ""),
+        };
+
+        if open.is_empty() || is_escaped {
+            rest = &rest[start + 1..];
+            continue;
+        }
+
+        let inner = &rest[start + open.len()..];
+        match inner.find(close) {
+            Some(end) if end > 0 && !inner[..end].contains(['*', '`']) => {
+                found.push(inner[..end].to_string());
+                rest = &inner[end + close.len()..];
+            }
+            _ => rest = inner,
+        }
+    }
+
+    found
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{extract, find_highlighted_code, Expected, Manifest};
+
+    #[test]
+    fn finds_highlighted_code() {
+        assert_eq!(
+            find_highlighted_code("produces *`142`* and `*281*`, not `12` or \\*\\*"),
+            ["142", "281"]
+        );
+    }
+
+    #[test]
+    fn extracts_examples_of_both_parts() {
+        let markdown = "## --- Day 1: Trebuchet?! ---\n\n\
+            For example:\n\n```\n1abc2\ntreb7uchet\n```\n\n\
+            Adding these together produces *`142`*.\n\n\
+            *What is the sum?*\n\n\
+            ## --- Part Two ---\n\n\
+            Now digits are spelled out. For example:\n\n```\ntwo1nine\n```\n\n\
+            Adding these together produces *`281`*.\n";
+
+        let extracted = extract(markdown);
+
+        assert_eq!(extracted.examples, ["1abc2\ntreb7uchet\n", "two1nine\n"]);
+        assert_eq!(
+            extracted.manifest,
+            Manifest {
+                part_one: Some(Expected {
+                    example: 1,
+                    answer: "142".into()
+                }),
+                part_two: Some(Expected {
+                    example: 2,
+                    answer: "281".into()
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_illustrations() {
+        let markdown = "\\--- Day 9: Mirage Maintenance ---\n----------\n\n\
+            For example:\n\n```\n0 3 6\n1 3 6\n\n```\n\n\
+            Visually, these sequences can be arranged like this:\n\n```\n0   3   6\n  3   3\n```\n\n\
+            This means `A` must be `*3*`, so the sum is `*114*`.\n\n\
+            \\--- Part Two ---\n----------\n\n\
+            Again consider the example from earlier:\n\n```\n0 3 6\n1 3 6\n```\n\n\
+            The sum is `*2*`.\n";
+
+        let extracted = extract(markdown);
+
+        assert_eq!(extracted.examples, ["0 3 6\n1 3 6\n"]);
+        assert_eq!(extracted.manifest.get(1).unwrap().answer, "114");
+        assert_eq!(
+            extracted.manifest.get(2),
+            Some(&Expected {
+                example: 1,
+                answer: "2".into()
+            })
+        );
+    }
+
+    #[test]
+    fn assigns_answer_to_introduced_example() {
+        let markdown = "For example:\n\n```\nRL\n```\n\nThis takes *`2`* steps.\n\n\
+            Repeat the instructions. For example, here is a situation that takes *`6`* steps:\n\n```\nLLR\n```\n";
+
+        let extracted = extract(markdown);
+
+        assert_eq!(extracted.examples, ["RL\n", "LLR\n"]);
+        assert_eq!(extracted.manifest.get(1).unwrap().example, 2);
+    }
+
+    #[test]
+    fn roundtrips_manifest() {
+        let manifest = Manifest {
+            part_one: Some(Expected {
+                example: 2,
+                answer: "CCC".into(),
+            }),
+            part_two: None,
+        };
+        let contents = toml::to_string(&manifest).unwrap();
+        assert_eq!(contents, "[part_one]\nexample = 2\nanswer = \"CCC\"\n");
+        assert_eq!(Manifest::parse(&contents).unwrap(), manifest);
+    }
+}
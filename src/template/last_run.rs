@@ -0,0 +1,63 @@
+/// Module that keeps the records of the last run of each day against its puzzle input in `data/<year>/last_run/<day>.jsonl`.
+/// The records are stored as one line of JSON per part, the same format that `--format json` prints.
+use std::{fs, io, path::Path};
+
+use crate::template::runner::PartRecord;
+use crate::{Day, Year};
+
+#[must_use]
+pub fn get_path(year: Year, day: Day) -> String {
+    format!("data/{year}/last_run/{day}.jsonl")
+}
+
+/// Replaces the stored records of `day`.
+pub fn write(year: Year, day: Day, records: &[PartRecord]) -> io::Result<()> {
+    let path = get_path(year, day);
+
+    if let Some(parent) = Path::new(&path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let contents: String = records
+        .iter()
+        .map(|record| record.to_json() + "\n")
+        .collect();
+
+    fs::write(path, contents)
+}
+
+/// Reads the stored records of `day`. Returns no records if the day has not been run yet.
+pub fn read(year: Year, day: Day) -> io::Result<Vec<PartRecord>> {
+    match fs::read_to_string(get_path(year, day)) {
+        Ok(contents) => Ok(parse(&contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e),
+    }
+}
+
+/// Parses records from the contents of a last run file, skipping lines that are not records.
+fn parse(contents: &str) -> Vec<PartRecord> {
+    contents.lines().filter_map(PartRecord::from_json).collect()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::parse;
+    use crate::day;
+    use crate::template::runner::Status;
+
+    #[test]
+    fn parses_records() {
+        let records = parse(
+            r#"{"day":1,"part":1,"answer":"42","duration_ns":166,"samples":1,"status":"solved"}
+not a record
+{"day":1,"part":2,"answer":null,"duration_ns":41,"samples":1,"status":"unsolved"}
+"#,
+        );
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].day, day!(1));
+        assert_eq!(records[0].answer.as_deref(), Some("42"));
+        assert_eq!(records[1].status, Status::Unsolved);
+    }
+}
@@ -0,0 +1,668 @@
+/// Module that converts puzzle pages of the website to Markdown and renders that Markdown in the terminal.
+///
+/// Only the subset of HTML used by puzzle descriptions is supported: headings, paragraphs, lists,
+/// code blocks, links and `<em>` highlights. Highlights become `*emphasis*` and are printed in bold,
+/// inline code is printed in italics.
+use std::fmt::Write;
+
+use crate::template::aoc_client::DEFAULT_BASE_URL;
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+/// Elements that never have children or a closing tag.
+const VOID_ELEMENTS: &[&str] = &["br", "hr", "img", "input", "link", "meta", "wbr"];
+
+/// Elements whose contents are not HTML.
+const RAW_ELEMENTS: &[&str] = &["script", "style"];
+
+#[derive(Debug)]
+enum Node {
+    Element {
+        name: String,
+        attributes: Vec<(String, String)>,
+        children: Vec<Node>,
+    },
+    Text(String),
+}
+
+impl Node {
+    fn element(name: String, attributes: Vec<(String, String)>) -> Self {
+        Node::Element {
+            name,
+            attributes,
+            children: vec![],
+        }
+    }
+
+    fn attribute(&self, key: &str) -> Option<&str> {
+        match self {
+            Node::Element { attributes, .. } => attributes
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str()),
+            Node::Text(_) => None,
+        }
+    }
+
+    fn has_class(&self, class: &str) -> bool {
+        self.attribute("class")
+            .is_some_and(|x| x.split_whitespace().any(|x| x == class))
+    }
+
+    /// Decoded text of the node and all of its descendants.
+    fn text(&self) -> String {
+        match self {
+            Node::Text(text) => text.clone(),
+            Node::Element { children, .. } => children.iter().map(Node::text).collect(),
+        }
+    }
+}
+
+/// Converts the puzzle page `html` to Markdown. All parts that are unlocked on the page are included,
+/// everything around them, e.g. the navigation and the answer form, is left out.
+#[must_use]
+pub fn from_html(html: &str) -> String {
+    let nodes = parse(html);
+
+    let mut articles = vec![];
+    find_articles(&nodes, &mut articles);
+
+    let blocks: Vec<String> = articles
+        .into_iter()
+        .flat_map(|article| match article {
+            Node::Element { children, .. } => convert_blocks(children),
+            Node::Text(_) => vec![],
+        })
+        .collect();
+
+    blocks.join("\n\n") + "\n"
+}
+
+fn find_articles<'a>(nodes: &'a [Node], articles: &mut Vec<&'a Node>) {
+    for node in nodes {
+        if let Node::Element { name, children, .. } = node {
+            if name == "article" && node.has_class("day-desc") {
+                articles.push(node);
+            } else {
+                find_articles(children, articles);
+            }
+        }
+    }
+}
+
+fn convert_blocks(nodes: &[Node]) -> Vec<String> {
+    let mut blocks = vec![];
+
+    for node in nodes {
+        let Node::Element { name, children, .. } = node else {
+            let text = collapse_whitespace(&escape(&node.text()));
+            if !text.trim().is_empty() {
+                blocks.push(text.trim().to_string());
+            }
+            continue;
+        };
+
+        match name.as_str() {
+            "h1" | "h2" | "h3" => blocks.push(format!("## {}", convert_inline(children).trim())),
+            "pre" => {
+                let code = node.text();
+                let newline = if code.ends_with('\n') { "" } else { "\n" };
+                blocks.push(format!("```\n{code}{newline}```"));
+            }
+            "ul" | "ol" => blocks.push(convert_list(children, 0)),
+            _ => {
+                let text = convert_inline(children);
+                if !text.trim().is_empty() {
+                    blocks.push(text.trim().to_string());
+                }
+            }
+        }
+    }
+
+    blocks
+}
+
+fn convert_list(items: &[Node], depth: usize) -> String {
+    let indent = "  ".repeat(depth);
+    let mut lines = vec![];
+
+    for item in items {
+        let Node::Element { name, children, .. } = item else {
+            continue;
+        };
+        if name != "li" {
+            continue;
+        }
+
+        let (nested, inline): (Vec<&Node>, Vec<&Node>) = children
+            .iter()
+            .partition(|x| matches!(x, Node::Element { name, .. } if name == "ul" || name == "ol"));
+
+        let text: String = inline.into_iter().map(convert_node).collect();
+        lines.push(format!("{indent}- {}", text.trim()));
+
+        for list in nested {
+            if let Node::Element { children, .. } = list {
+                lines.push(convert_list(children, depth + 1));
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn convert_inline(nodes: &[Node]) -> String {
+    nodes.iter().map(convert_node).collect()
+}
+
+fn convert_node(node: &Node) -> String {
+    let Node::Element { name, children, .. } = node else {
+        return collapse_whitespace(&escape(&node.text()));
+    };
+
+    match name.as_str() {
+        "em" => format!("*{}*", convert_inline(children)),
+        "code" => match children.as_slice() {
+            // highlighted code, e.g. the answer of an example.
+            [child @ Node::Element { name, .. }] if name == "em" => {
+                format!("*`{}`*", child.text())
+            }
+            _ => format!("`{}`", node.text()),
+        },
+        "a" => {
+            let text = convert_inline(children);
+            match node.attribute("href") {
+                Some(href) if href.starts_with('/') => {
+                    format!("[{text}]({DEFAULT_BASE_URL}{href})")
+                }
+                Some(href) => format!("[{text}]({href})"),
+                None => text,
+            }
+        }
+        "br" => "\n".into(),
+        _ => convert_inline(children),
+    }
+}
+
+/// Escapes the characters of `text` that would otherwise be read as Markdown.
+/// A `#` is only escaped at the start of a line, where it would begin a heading.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut is_line_start = true;
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '`' | '[' | ']' | '_') || (c == '#' && is_line_start) {
+            out.push('\\');
+        }
+        out.push(c);
+        is_line_start = (is_line_start && c.is_whitespace()) || c == '\n';
+    }
+    out
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut was_whitespace = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !was_whitespace {
+                out.push(' ');
+            }
+            was_whitespace = true;
+        } else {
+            out.push(c);
+            was_whitespace = false;
+        }
+    }
+    out
+}
+
+/// Parses `html` into a tree. Unclosed elements are closed at the end of their parent,
+/// closing tags without a matching element are ignored.
+fn parse(html: &str) -> Vec<Node> {
+    // open elements, the root is the document itself.
+    let mut stack = vec![Node::element(String::new(), vec![])];
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            push_text(&mut stack, rest);
+            break;
+        };
+
+        push_text(&mut stack, &rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            push_text(&mut stack, rest);
+            break;
+        };
+
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim().to_ascii_lowercase();
+            let index = stack
+                .iter()
+                .skip(1)
+                .rposition(|x| matches!(x, Node::Element { name: x, .. } if *x == name));
+            if let Some(index) = index {
+                while stack.len() > index + 1 {
+                    close_element(&mut stack);
+                }
+            }
+            continue;
+        }
+
+        let (name, attributes) = parse_tag(tag);
+
+        if RAW_ELEMENTS.contains(&name.as_str()) {
+            let closing = format!("</{name}");
+            rest = rest
+                .find(&closing)
+                .and_then(|end| rest[end..].find('>').map(|x| &rest[end + x + 1..]))
+                .unwrap_or("");
+            continue;
+        }
+
+        if VOID_ELEMENTS.contains(&name.as_str()) || tag.ends_with('/') {
+            push_node(&mut stack, Node::element(name, attributes));
+        } else {
+            stack.push(Node::element(name, attributes));
+        }
+    }
+
+    while stack.len() > 1 {
+        close_element(&mut stack);
+    }
+
+    match stack.pop() {
+        Some(Node::Element { children, .. }) => children,
+        _ => vec![],
+    }
+}
+
+/// Adds `node` to the innermost open element of `stack`.
+fn push_node(stack: &mut [Node], node: Node) {
+    if let Some(Node::Element { children, .. }) = stack.last_mut() {
+        children.push(node);
+    }
+}
+
+fn push_text(stack: &mut [Node], text: &str) {
+    if !text.is_empty() {
+        push_node(stack, Node::Text(decode_entities(text)));
+    }
+}
+
+fn close_element(stack: &mut Vec<Node>) {
+    if let Some(node) = stack.pop() {
+        push_node(stack, node);
+    }
+}
+
+/// Parses the name and attributes of an opening tag, e.g. `a href="/2023"`.
+fn parse_tag(tag: &str) -> (String, Vec<(String, String)>) {
+    let tag = tag.trim_end_matches('/');
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = tag[..name_end].to_ascii_lowercase();
+
+    let mut attributes = vec![];
+    let mut rest = tag[name_end..].trim_start();
+
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+
+        let value = if let Some(value) = rest.strip_prefix('=') {
+            let value = value.trim_start();
+            let (value, remainder) = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let value = &value[1..];
+                    let end = value.find(quote).unwrap_or(value.len());
+                    (&value[..end], value.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                    (&value[..end], &value[end..])
+                }
+            };
+            rest = remainder.trim_start();
+            decode_entities(value)
+        } else {
+            String::new()
+        };
+
+        if !key.is_empty() {
+            attributes.push((key, value));
+        }
+    }
+
+    (name, attributes)
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|x| u32::from_str_radix(x, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|x| x.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Renders `markdown` with ANSI styles for the terminal. Headings and highlights are printed in bold,
+/// inline code in italics and code blocks are indented. Links are reduced to their text.
+///
+/// Puzzle descriptions that were downloaded with aoc-cli, with setext headings and escaped text, are supported too.
+#[must_use]
+pub fn render(markdown: &str) -> String {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut out = String::new();
+    let mut is_code_block = false;
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+
+        if line.trim_start().starts_with("```") {
+            is_code_block = !is_code_block;
+            continue;
+        }
+
+        if is_code_block {
+            let _ = writeln!(out, "    {line}");
+            continue;
+        }
+
+        let is_setext_heading = !line.trim().is_empty()
+            && lines.get(i).is_some_and(|next| {
+                next.len() >= 3
+                    && (next.chars().all(|c| c == '-') || next.chars().all(|c| c == '='))
+            });
+
+        if is_setext_heading {
+            i += 1;
+            let _ = writeln!(out, "{ANSI_BOLD}{}{ANSI_RESET}", render_inline(line));
+        } else if let Some(heading) = line.strip_prefix('#') {
+            let heading = heading.trim_start_matches('#').trim();
+            let _ = writeln!(out, "{ANSI_BOLD}{}{ANSI_RESET}", render_inline(heading));
+        } else if let Some(item) = get_list_item(line) {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let _ = writeln!(out, "{indent}  • {}", render_inline(item));
+        } else {
+            let _ = writeln!(out, "{}", render_inline(line));
+        }
+    }
+
+    out
+}
+
+fn get_list_item(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    line.strip_prefix("- ").or_else(|| line.strip_prefix("* "))
+}
+
+/// Renders emphasis, code and links of a single line.
+fn render_inline(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut is_bold = false;
+    let mut is_code = false;
+    // whether the emphasis was opened inside the current code span.
+    let mut is_highlighted_code = false;
+    // whether each open bracket starts a link, the targets of links are skipped.
+    let mut brackets = vec![];
+    let mut previous = None;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if !is_code && chars.peek().is_some_and(char::is_ascii_punctuation) => {
+                out.extend(chars.next());
+            }
+            '`' => {
+                is_code = !is_code;
+                if is_code {
+                    out.push_str(ANSI_ITALIC);
+                } else {
+                    is_bold &= !is_highlighted_code;
+                    is_highlighted_code = false;
+                    reset_style(&mut out, is_bold, is_code);
+                }
+            }
+            // aoc-cli writes highlighted code as `*142*`, any other `*` in code is literal.
+            '*' if is_code => {
+                let next = chars.peek().copied();
+                if !is_bold && previous == Some('`') && next.is_some_and(|x| x != '`') {
+                    is_bold = true;
+                    is_highlighted_code = true;
+                    out.push_str(ANSI_BOLD);
+                } else if is_highlighted_code && next == Some('`') {
+                    is_bold = false;
+                    is_highlighted_code = false;
+                    reset_style(&mut out, is_bold, is_code);
+                } else {
+                    out.push(c);
+                }
+            }
+            '*' => {
+                is_bold = !is_bold;
+                if is_bold {
+                    out.push_str(ANSI_BOLD);
+                } else {
+                    reset_style(&mut out, is_bold, is_code);
+                }
+            }
+            '[' if !is_code => {
+                let is_link = is_link_text(chars.clone());
+                if !is_link {
+                    out.push(c);
+                }
+                brackets.push(is_link);
+            }
+            ']' if !is_code && !brackets.is_empty() => {
+                if brackets.pop() != Some(true) {
+                    out.push(c);
+                } else if chars.peek() == Some(&'(') {
+                    let mut depth = 0;
+                    for c in chars.by_ref() {
+                        match c {
+                            '(' => depth += 1,
+                            ')' if depth == 1 => break,
+                            ')' => depth -= 1,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            c => out.push(c),
+        }
+        previous = Some(c);
+    }
+
+    if is_bold || is_code {
+        out.push_str(ANSI_RESET);
+    }
+
+    out
+}
+
+/// Whether the bracket before `rest` opens the text of a link, i.e. its closing bracket is directly followed by `(`.
+fn is_link_text(mut rest: impl Iterator<Item = char>) -> bool {
+    let mut depth = 0;
+    while let Some(c) = rest.next() {
+        match c {
+            '\\' => {
+                rest.next();
+            }
+            '[' => depth += 1,
+            ']' if depth == 0 => return rest.next() == Some('('),
+            ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Resets the style and re-applies the styles that are still active.
+fn reset_style(out: &mut String, is_bold: bool, is_code: bool) {
+    out.push_str(ANSI_RESET);
+    if is_bold {
+        out.push_str(ANSI_BOLD);
+    }
+    if is_code {
+        out.push_str(ANSI_ITALIC);
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{from_html, render};
+    use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en-us">
+<head><title>Day 1 - Advent of Code 2023</title><script>var x = "<article>";</script></head>
+<body>
+<header><h1 class="title-global"><a href="/">Advent of Code</a></h1></header>
+<main>
+<article class="day-desc"><h2>--- Day 1: Trebuchet?! ---</h2><p>Something is wrong with <em>global snow production</em>, see the <a href="/2023/about">about page</a>.</p>
+<p>For example:</p>
+<pre><code>1abc2
+pqr3stu8vwx
+</code></pre>
+<p>Adding these together produces <code><em>142</em></code> &amp; <code>a*b</code>.</p>
+<ul>
+<li>Seed <code>79</code>, <em>location <code>82</code></em>.</li>
+<li>Nested:<ul><li>inner</li></ul></li>
+</ul>
+</article>
+<p>Your puzzle answer was <code>54940</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>It's <span title="easter egg">spelled out</span>: <code>one</code>.</p></article>
+<form method="post" action="2/answer"><input type="text" name="answer"/></form>
+</main>
+</body>
+</html>"#;
+
+    #[test]
+    fn converts_both_parts() {
+        assert_eq!(
+            from_html(PAGE),
+            "## --- Day 1: Trebuchet?! ---\n\n\
+             Something is wrong with *global snow production*, see the [about page](https://adventofcode.com/2023/about).\n\n\
+             For example:\n\n\
+             ```\n1abc2\npqr3stu8vwx\n```\n\n\
+             Adding these together produces *`142`* & `a*b`.\n\n\
+             - Seed `79`, *location `82`*.\n\
+             - Nested:\n  - inner\n\n\
+             ## --- Part Two ---\n\n\
+             It's spelled out: `one`.\n"
+        );
+    }
+
+    #[test]
+    fn escapes_markdown_in_text() {
+        let html = r#"<article class="day-desc"><p>two stars: ** and a `tick`</p></article>"#;
+        assert_eq!(from_html(html), "two stars: \\*\\* and a \\`tick\\`\n");
+    }
+
+    #[test]
+    fn renders_styles() {
+        let rendered = render(
+            "## --- Part Two ---\n\nproduces *`142`*, see [the page](https://example.com).\n",
+        );
+        assert_eq!(
+            rendered,
+            format!(
+                "{ANSI_BOLD}--- Part Two ---{ANSI_RESET}\n\nproduces {ANSI_BOLD}{ANSI_ITALIC}142{ANSI_RESET}{ANSI_BOLD}{ANSI_RESET}, see the page.\n"
+            )
+        );
+
+        let rendered = render(&from_html(
+            "<article class=\"day-desc\"><p>A gear is any <code>*</code> symbol, see <code>a\\*b</code>.</p></article>",
+        ));
+        assert_eq!(
+            rendered,
+            format!("A gear is any {ANSI_ITALIC}*{ANSI_RESET} symbol, see {ANSI_ITALIC}a\\*b{ANSI_RESET}.\n")
+        );
+    }
+
+    #[test]
+    fn escapes_brackets_and_headings() {
+        let html = "<article class=\"day-desc\"><p># of [a_b] cells, not #1</p></article>";
+        let markdown = from_html(html);
+        assert_eq!(markdown, "\\# of \\[a\\_b\\] cells, not #1\n");
+        assert_eq!(render(&markdown), "# of [a_b] cells, not #1\n");
+    }
+
+    #[test]
+    fn renders_brackets_in_prose() {
+        assert_eq!(render("a [b] c\n"), "a [b] c\n");
+        assert_eq!(render("a [b] [c](x) [d\n"), "a [b] c [d\n");
+        assert_eq!(
+            render("see [the [1] page](https://example.com/(a)) now\n"),
+            "see the [1] page now\n"
+        );
+    }
+
+    #[test]
+    fn renders_code_blocks_and_lists() {
+        let rendered = render("```\n1*2\n```\n\n- a\n  - b\n");
+        assert_eq!(rendered, "    1*2\n\n  • a\n    • b\n");
+    }
+
+    #[test]
+    fn renders_aoc_cli_markdown() {
+        let rendered =
+            render("\\--- Day 1: Trebuchet?! ---\n----------\n\nstars: \\*\\*, sum: `*281*`\n");
+        assert_eq!(
+            rendered,
+            format!("{ANSI_BOLD}--- Day 1: Trebuchet?! ---{ANSI_RESET}\n\nstars: **, sum: {ANSI_ITALIC}{ANSI_BOLD}281{ANSI_RESET}{ANSI_ITALIC}{ANSI_RESET}\n")
+        );
+    }
+}
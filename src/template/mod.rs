@@ -1,36 +1,281 @@
-use crate::Day;
+use crate::{Day, Year};
+use std::convert::Infallible;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::{env, fs};
 
+pub mod alloc;
+pub mod answers;
 pub mod aoc_cli;
+pub mod aoc_client;
+pub mod baselines;
 pub mod commands;
+pub mod encryption;
+mod error;
+pub mod examples;
+pub mod last_run;
+pub mod markdown;
+pub mod panics;
 pub mod readme_benchmarks;
 pub mod runner;
+mod solution;
+pub mod spans;
+pub mod stars;
+pub mod submissions;
+pub mod watch;
+
+pub use error::Error;
+pub use solution::{Registry, Solution};
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
+pub const ANSI_RED: &str = "\x1b[31m";
+pub const ANSI_GREEN: &str = "\x1b[32m";
+pub const ANSI_CLEAR: &str = "\x1b[2J\x1b[H";
 
-/// Helper function that reads a text file to a string.
+/// Helper function that reads a text file to a string. Encrypted inputs are decrypted, see [`encryption`].
 #[must_use]
-pub fn read_file(folder: &str, day: Day) -> String {
+pub fn read_file(folder: &str, year: Year, day: Day) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
+    let filepath = cwd.join(get_path(folder, year, day));
+    let f = encryption::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
-/// Creates the constant `DAY` and sets up the input and runner for each part.
+/// Helper function that reads example `number` of `day`, as extracted by `cargo examples`.
+/// Example 1 is the same file that [`read_file`] reads from the `examples` folder.
+#[must_use]
+pub fn read_example(year: Year, day: Day, number: usize) -> String {
+    let cwd = env::current_dir().unwrap();
+    let filepath = cwd.join(examples::get_path(year, day, number));
+    let f = fs::read_to_string(filepath);
+    f.expect("could not open example file")
+}
+
+/// Helper function that reads the example of `part` of `day` and the answer it should return, as extracted by
+/// `cargo examples`. Without an expected answer for the part, this reads the first example and returns [`None`].
+#[must_use]
+pub fn read_expected(year: Year, day: Day, part: u8) -> (String, Option<String>) {
+    let manifest = examples::read_manifest(year, day).expect("could not read examples manifest");
+    match manifest.as_ref().and_then(|x| x.get(part)) {
+        Some(expected) => (
+            read_example(year, day, expected.example),
+            Some(expected.answer.clone()),
+        ),
+        None => (read_file("examples", year, day), None),
+    }
+}
+
+/// Path of the text file for `day` in `data/<year>/<folder>`, relative to the project root.
+#[must_use]
+pub fn get_path(folder: &str, year: Year, day: Day) -> PathBuf {
+    PathBuf::from("data")
+        .join(year.to_string())
+        .join(folder)
+        .join(format!("{day}.txt"))
+}
+
+/// Name of the binary of a solution, e.g. `2023_05`.
+#[must_use]
+pub fn get_bin_name(year: Year, day: Day) -> String {
+    format!("{year}_{day}")
+}
+
+/// Where a solution binary reads its input from.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum InputSource {
+    /// The puzzle input in `data/<year>/inputs`.
+    #[default]
+    Puzzle,
+    /// The example in `data/<year>/examples`, selected with `--example`.
+    Example,
+    /// An arbitrary file, selected with `--input <path>`.
+    File(PathBuf),
+    /// Standard input, selected with `--input -`.
+    Stdin,
+}
+
+impl InputSource {
+    /// Reads the `--input <path>` and `--example` arguments passed to a solution binary.
+    pub fn from_args() -> Result<Self, Error> {
+        let args: Vec<String> = env::args().collect();
+
+        if let Some(i) = args.iter().position(|x| x == "--input") {
+            return match args.get(i + 1) {
+                Some(value) => Ok(value.parse().unwrap_or_default()),
+                None => Err(Error::Usage(
+                    "Unexpected command-line input. Format: cargo solve 1 --input <path>".into(),
+                )),
+            };
+        }
+
+        if args.iter().any(|x| x == "--example") {
+            return Ok(Self::Example);
+        }
+
+        Ok(Self::Puzzle)
+    }
+
+    /// Converts the source back to the arguments read by [`InputSource::from_args`].
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            Self::Puzzle => vec![],
+            Self::Example => vec!["--example".into()],
+            Self::File(path) => vec!["--input".into(), path.to_string_lossy().into_owned()],
+            Self::Stdin => vec!["--input".into(), "-".into()],
+        }
+    }
+
+    /// Path of the file this source reads for `day`, [`None`] for stdin.
+    #[must_use]
+    pub fn path(&self, year: Year, day: Day) -> Option<PathBuf> {
+        match self {
+            Self::Puzzle => Some(get_path("inputs", year, day)),
+            Self::Example => Some(get_path("examples", year, day)),
+            Self::File(path) => Some(path.clone()),
+            Self::Stdin => None,
+        }
+    }
+
+    /// Reads the input for `day` from this source.
+    pub fn read(&self, year: Year, day: Day) -> Result<String, Error> {
+        let result = match self.path(year, day) {
+            Some(path) => encryption::read_to_string(path),
+            None => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input).map(|_| input)
+            }
+        };
+
+        result.map_err(Error::io(format!("could not read input from {self}")))
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = Infallible;
+
+    /// Parses the value of `--input`: `-` selects stdin, anything else is a file path.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "-" => Self::Stdin,
+            path => Self::File(path.into()),
+        })
+    }
+}
+
+impl std::fmt::Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Puzzle => f.write_str("puzzle input"),
+            Self::Example => f.write_str("example"),
+            Self::File(path) => write!(f, "\"{}\"", path.display()),
+            Self::Stdin => f.write_str("stdin"),
+        }
+    }
+}
+
+/// Creates the constants `YEAR` and `DAY` and sets up the input and runner for each part.
+///
+/// This also defines `Solver`, which implements [`Solution`] for the day.
+/// The main binary uses it to run the day in-process.
+///
+/// # Parsing the input once
+/// Passing a parse function as the third argument, e.g. `solution!(2023, 10, parse)`, runs it once per input.
+/// Both parts then receive a reference to its result instead of `&str`, and the parse time is reported on its own.
+/// The result is shared with the parts, so it can not borrow from the input.
+///
+/// ```ignore
+/// advent_of_code::solution!(2023, 10, parse);
+///
+/// fn parse(input: &str) -> Vec<u32> { /* ... */ }
+/// pub fn part_one(input: &Vec<u32>) -> Option<u32> { /* ... */ }
+/// pub fn part_two(input: &Vec<u32>) -> Option<u32> { /* ... */ }
+/// ```
+///
+/// # Single-part days
+/// Days with only one puzzle part, like the last day of advent, end with `single_part`.
+/// They only define `part_one`.
+///
+/// ```ignore
+/// advent_of_code::solution!(2023, 25, single_part);
+/// advent_of_code::solution!(2023, 25, parse, single_part);
+/// ```
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
+    (@common $year:expr, $day:expr, $parts:expr, |$input:ident, $options:ident| $run:block) => {
+        /// The year of the current day.
+        const YEAR: advent_of_code::Year = advent_of_code::year!($year);
+
         /// The current day.
         const DAY: advent_of_code::Day = advent_of_code::day!($day);
 
+        /// The solution of the current day.
+        pub struct Solver;
+
+        #[allow(dead_code)]
         fn main() {
-            use advent_of_code::template::runner::*;
-            let input = advent_of_code::template::read_file("inputs", DAY);
-            run_part(part_one, &input, DAY, 1);
-            run_part(part_two, &input, DAY, 2);
+            if let Err(e) = advent_of_code::template::runner::run_binary(&Solver) {
+                e.exit();
+            }
+        }
+
+        impl advent_of_code::template::Solution for Solver {
+            fn year(&self) -> advent_of_code::Year {
+                YEAR
+            }
+
+            fn day(&self) -> advent_of_code::Day {
+                DAY
+            }
+
+            fn parts(&self) -> u8 {
+                $parts
+            }
+
+            fn run(
+                &self,
+                $input: &str,
+                $options: &advent_of_code::template::runner::RunOptions,
+            ) -> Vec<advent_of_code::template::runner::PartRecord> {
+                use advent_of_code::template::runner::*;
+                $run
+            }
         }
     };
+
+    (@parts $year:expr, $day:expr, [$($func:ident = $part:literal),+]) => {
+        advent_of_code::solution!(@common $year, $day, [$($part),+].len() as u8, |input, options| {
+            let input = std::sync::Arc::from(input);
+            vec![$(run_part($func, &input, YEAR, DAY, $part, options)),+]
+        });
+    };
+
+    (@parse $year:expr, $day:expr, $parse:expr, [$($func:ident = $part:literal),+]) => {
+        advent_of_code::solution!(@common $year, $day, [$($part),+].len() as u8, |input, options| {
+            let (parsed, parse_record) = run_parse($parse, &std::sync::Arc::from(input), DAY, options);
+            let Some(parsed) = parsed.map(std::sync::Arc::new) else {
+                return vec![parse_record];
+            };
+            vec![parse_record, $(run_part($func, &parsed, YEAR, DAY, $part, options)),+]
+        });
+    };
+
+    ($year:expr, $day:expr) => {
+        advent_of_code::solution!(@parts $year, $day, [part_one = 1, part_two = 2]);
+    };
+
+    ($year:expr, $day:expr, single_part) => {
+        advent_of_code::solution!(@parts $year, $day, [part_one = 1]);
+    };
+
+    ($year:expr, $day:expr, $parse:expr, single_part) => {
+        advent_of_code::solution!(@parse $year, $day, $parse, [part_one = 1]);
+    };
+
+    ($year:expr, $day:expr, $parse:expr) => {
+        advent_of_code::solution!(@parse $year, $day, $parse, [part_one = 1, part_two = 2]);
+    };
 }
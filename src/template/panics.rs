@@ -0,0 +1,76 @@
+/// Module that catches panics of solution parts and turns them into readable messages.
+/// A panic hook records the message and location of caught panics instead of printing them.
+/// The hook only knows about the thread that called [`catch`], panics on threads spawned by the part are still printed.
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+thread_local! {
+    /// Whether the current thread is running inside [`catch`].
+    static IS_CATCHING: Cell<bool> = const { Cell::new(false) };
+    /// Message of the last panic caught on the current thread.
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Runs `func` and returns its result, or `<message> at <location>` if it panicked.
+/// Panics outside of [`catch`] are still reported by the previous panic hook.
+pub fn catch<T>(func: impl FnOnce() -> T) -> Result<T, String> {
+    INSTALL_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if IS_CATCHING.get() {
+                let message = match info.location() {
+                    Some(location) => format!("{} at {location}", get_message(info.payload())),
+                    None => get_message(info.payload()),
+                };
+                LAST_PANIC.set(Some(message));
+            } else {
+                previous_hook(info);
+            }
+        }));
+    });
+
+    let was_catching = IS_CATCHING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(func));
+    IS_CATCHING.set(was_catching);
+
+    result.map_err(|payload| {
+        LAST_PANIC
+            .take()
+            .unwrap_or_else(|| get_message(payload.as_ref()))
+    })
+}
+
+/// Extracts the message of a panic payload, which is a string for all panics raised through `panic!`.
+fn get_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::catch;
+
+    #[test]
+    fn returns_result_without_panic() {
+        assert_eq!(catch(|| 42), Ok(42));
+    }
+
+    #[test]
+    fn returns_message_and_location_of_panic() {
+        let error = catch(|| -> u32 { panic!("bad input {}", 42) }).unwrap_err();
+        assert!(error.starts_with("bad input 42 at src/template/panics.rs:"));
+
+        let error = catch(|| None::<u32>.unwrap()).unwrap_err();
+        assert!(error.starts_with("called `Option::unwrap()` on a `None` value at "));
+    }
+}
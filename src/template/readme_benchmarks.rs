@@ -1,26 +1,22 @@
 /// Module that updates the readme me with timing information.
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+use std::fs;
 
-use crate::Day;
+use crate::template::{get_bin_name, Error};
+use crate::{Day, Year};
 
-static MARKER: &str = "<!--- benchmarking table --->";
-
-#[derive(Debug)]
-pub enum Error {
-    Parser(String),
-    IO(io::Error),
-}
-
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
-        Error::IO(e)
-    }
+/// Surrounds the table of a year, e.g. `<!--- benchmarking table 2023 --->`.
+fn get_marker(year: Year) -> String {
+    format!("<!--- benchmarking table {year} --->")
 }
 
 #[derive(Clone)]
 pub struct Timings {
     pub day: Day,
+    /// Single-part days have no timing for part 2.
+    pub is_single_part: bool,
+    /// Only set for solutions that parse their input once.
+    pub parse: Option<String>,
     pub part_1: Option<String>,
     pub part_2: Option<String>,
     pub total_nanos: f64,
@@ -32,12 +28,12 @@ pub struct TablePosition {
 }
 
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(year: Year, day: Day) -> String {
+    format!("./src/bin/{}.rs", get_bin_name(year, day))
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+fn locate_table(readme: &str, marker: &str) -> Result<TablePosition, Error> {
+    let matches: Vec<_> = readme.match_indices(marker).collect();
 
     if matches.len() > 2 {
         return Err(Error::Parser(
@@ -58,71 +54,103 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Vec<Timings>, total_millis: f64) -> String {
-    let header = format!("{prefix} Benchmarks");
+fn construct_table(prefix: &str, year: Year, timings: Vec<Timings>, total_millis: f64) -> String {
+    let header = format!("{prefix} {year} Benchmarks");
 
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
+    // only show a column for parse times if any solution parses its input separately.
+    let has_parse = timings.iter().any(|x| x.parse.is_some());
+
+    let mut lines: Vec<String> = vec![get_marker(year), header, String::new()];
+
+    if has_parse {
+        lines.push("| Day | Parse | Part 1 | Part 2 |".into());
+        lines.push("| :---: | :---: | :---: | :---:  |".into());
+    } else {
+        lines.push("| Day | Part 1 | Part 2 |".into());
+        lines.push("| :---: | :---: | :---:  |".into());
+    }
 
     for timing in timings {
-        let path = get_path_for_bin(timing.day);
+        let path = get_path_for_bin(year, timing.day);
+        let parse = if has_parse {
+            format!(" `{}` |", timing.parse.unwrap_or_else(|| "-".into()))
+        } else {
+            String::new()
+        };
+        let part_2 = if timing.is_single_part {
+            "—".to_string()
+        } else {
+            format!("`{}`", timing.part_2.unwrap_or_else(|| "-".into()))
+        };
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
+            "| [Day {}]({}) |{} `{}` | {} |",
             timing.day.into_inner(),
             path,
+            parse,
             timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            part_2
         ));
     }
 
     lines.push(String::new());
     lines.push(format!("**Total: {total_millis:.2}ms**"));
-    lines.push(MARKER.into());
+    lines.push(get_marker(year));
 
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+fn update_content(
+    s: &mut String,
+    year: Year,
+    timings: Vec<Timings>,
+    total_millis: f64,
+) -> Result<(), Error> {
+    let positions = locate_table(s, &get_marker(year))?;
+    let table = construct_table("##", year, timings, total_millis);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
-pub fn update(timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
+/// Replaces the benchmark table of `year` in the readme. The table has to be surrounded by two markers for that year.
+pub fn update(year: Year, timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
     let path = "README.md";
-    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    update_content(&mut readme, timings, total_millis)?;
-    fs::write(path, &readme)?;
+    let contents = fs::read(path).map_err(Error::io(format!("could not read \"{path}\"")))?;
+    let mut readme = String::from_utf8_lossy(&contents).to_string();
+    update_content(&mut readme, year, timings, total_millis)?;
+    fs::write(path, &readme).map_err(Error::io(format!("could not write \"{path}\"")))?;
     Ok(())
 }
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{update_content, Timings, MARKER};
-    use crate::day;
+    use super::{update_content, Timings};
+    use crate::{day, year, Year};
+
+    const YEAR: Year = year!(2023);
+    const MARKER: &str = "<!--- benchmarking table 2023 --->";
 
     fn get_mock_timings() -> Vec<Timings> {
         vec![
             Timings {
                 day: day!(1),
+                is_single_part: false,
+                parse: None,
                 part_1: Some("10ms".into()),
                 part_2: Some("20ms".into()),
                 total_nanos: 3e+10,
             },
             Timings {
                 day: day!(2),
+                is_single_part: false,
+                parse: None,
                 part_1: Some("30ms".into()),
                 part_2: Some("40ms".into()),
                 total_nanos: 7e+10,
             },
             Timings {
                 day: day!(4),
+                is_single_part: false,
+                parse: None,
                 part_1: Some("40ms".into()),
                 part_2: Some("50ms".into()),
                 total_nanos: 9e+10,
@@ -134,53 +162,94 @@ mod tests {
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, YEAR, get_mock_timings(), 190.0).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, YEAR, get_mock_timings(), 190.0).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        assert_eq!(s.contains("## Benchmarks"), true);
+        update_content(&mut s, YEAR, get_mock_timings(), 190.0).unwrap();
+        assert_eq!(s.contains("## 2023 Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, YEAR, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, YEAR, get_mock_timings(), 190.0).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
-        assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
+        assert_eq!(
+            s.matches("## 2023 Benchmarks").collect::<Vec<&str>>().len(),
+            1
+        );
     }
 
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, YEAR, get_mock_timings(), 190.0).unwrap();
         let expected = [
             "foo",
             "bar",
-            "<!--- benchmarking table --->",
-            "## Benchmarks",
+            "<!--- benchmarking table 2023 --->",
+            "## 2023 Benchmarks",
             "",
             "| Day | Part 1 | Part 2 |",
             "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| [Day 1](./src/bin/2023_01.rs) | `10ms` | `20ms` |",
+            "| [Day 2](./src/bin/2023_02.rs) | `30ms` | `40ms` |",
+            "| [Day 4](./src/bin/2023_04.rs) | `40ms` | `50ms` |",
             "",
             "**Total: 190.00ms**",
-            "<!--- benchmarking table --->",
+            "<!--- benchmarking table 2023 --->",
             "baz",
         ]
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_benchmarks_with_parse() {
+        let mut timings = get_mock_timings();
+        timings[1].parse = Some("5ms".into());
+
+        let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
+        update_content(&mut s, YEAR, timings, 195.0).unwrap();
+        let expected = [
+            "foo",
+            "bar",
+            "<!--- benchmarking table 2023 --->",
+            "## 2023 Benchmarks",
+            "",
+            "| Day | Parse | Part 1 | Part 2 |",
+            "| :---: | :---: | :---: | :---:  |",
+            "| [Day 1](./src/bin/2023_01.rs) | `-` | `10ms` | `20ms` |",
+            "| [Day 2](./src/bin/2023_02.rs) | `5ms` | `30ms` | `40ms` |",
+            "| [Day 4](./src/bin/2023_04.rs) | `-` | `40ms` | `50ms` |",
+            "",
+            "**Total: 195.00ms**",
+            "<!--- benchmarking table 2023 --->",
+            "baz",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+
+    #[test]
+    fn format_benchmarks_with_single_part() {
+        let mut timings = get_mock_timings();
+        timings[2].is_single_part = true;
+        timings[2].part_2 = None;
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(&mut s, YEAR, timings, 190.0).unwrap();
+        assert!(s.contains("| [Day 4](./src/bin/2023_04.rs) | `40ms` | — |"));
+    }
 }
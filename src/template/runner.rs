@@ -1,167 +1,1260 @@
 /// Encapsulates code that interacts with solution functions.
-use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
-use crate::Day;
+use crate::template::alloc::{self, AllocStats};
+use crate::template::{
+    answers, aoc_cli, last_run, panics,
+    spans::{self, SpanStats},
+    submissions::{self, Submission, Verdict},
+    Error, InputSource, Solution, ANSI_ITALIC, ANSI_RESET,
+};
+use crate::{Day, Year};
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
-use std::io::{stdout, Write};
-use std::process::Output;
-use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::io::{self, stdout, Write};
+use std::str::FromStr;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use std::{cmp, env, panic, thread};
 
 use super::ANSI_BOLD;
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Entry point of a solution binary: runs `solution` against the input and with the options passed on the command-line.
+/// Fails if a part panicked, timed out or returned a wrong answer.
+pub fn run_binary(solution: &dyn Solution) -> Result<(), Error> {
+    let options = RunOptions::from_args()?;
+
+    if let Some(part) = options.submit {
+        if part == 0 || part > solution.parts() {
+            return Err(Error::Usage(format!(
+                "Day {} has no part {part} to submit.",
+                solution.day()
+            )));
+        }
+
+        aoc_cli::check()?;
+    }
+
+    let input = InputSource::from_args()?.read(solution.year(), solution.day())?;
+    let records = solution.run(&input, &options);
+    save_last_run(solution.year(), solution.day(), &records, &options);
+    check_records(&records, true)
+}
+
+/// Fails with the first kind of failure found in `records`: panics, then timeouts, then wrong answers if `check_answers` is set.
+pub fn check_records<'a>(
+    records: impl IntoIterator<Item = &'a PartRecord>,
+    check_answers: bool,
+) -> Result<(), Error> {
+    let (mut panicked, mut timed_out, mut wrong) = (0, 0, 0);
+
+    for record in records {
+        match record.status {
+            Status::Panicked => panicked += 1,
+            Status::TimedOut => timed_out += 1,
+            Status::Wrong if check_answers => wrong += 1,
+            _ => {}
+        }
+    }
+
+    match (panicked, timed_out, wrong) {
+        (0, 0, 0) => Ok(()),
+        (0, 0, wrong) => Err(Error::WrongAnswer(wrong)),
+        (0, timed_out, _) => Err(Error::TimedOut(timed_out)),
+        (panicked, _, _) => Err(Error::Panicked(panicked)),
+    }
+}
+
+/// Stores `records` as the last run of `day`, if they belong to a run against the puzzle input.
+pub fn save_last_run(year: Year, day: Day, records: &[PartRecord], options: &RunOptions) {
+    if !options.verify || records.is_empty() {
+        return;
+    }
+
+    if let Err(e) = last_run::write(year, day, records) {
+        eprintln!("Failed to write \"{}\": {e}", last_run::get_path(year, day));
+    }
+}
+
+/// Runs a solution part, prints its result in the configured format and returns it as a [`PartRecord`].
+///
+/// With a timeout, the part runs on a separate thread. If it does not finish in time,
+/// it is left running in the background and reported as timed out. Benching the part stops at the same timeout.
+/// Panics of the part are caught and reported with their message and location.
+pub fn run_part<I, T, F>(
+    func: F,
+    input: &Arc<I>,
+    year: Year,
+    day: Day,
+    part: u8,
+    options: &RunOptions,
+) -> PartRecord
+where
+    I: ?Sized + Send + Sync + 'static,
+    T: Display + Send + 'static,
+    F: Fn(&I) -> Option<T> + Clone + Send + 'static,
+{
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let run = run_timed(func, input, options, |result| {
+        if !options.quiet && options.format == OutputFormat::Human {
+            let mut stdout = stdout();
+            let _ = write_result(&mut stdout, result, &part_str, "");
+
+            if options.bench.is_some() {
+                print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+            }
+
+            let _ = stdout.flush();
+        }
+    });
+
+    let run = match run {
+        Ok(run) => run,
+        Err(failure) => return print_failure(day, part, failure, options),
+    };
+
+    let expected = match options.verify.then(|| answers::read(year, day)) {
+        Some(Ok(answers)) => answers.and_then(|x| x.get(part).map(ToString::to_string)),
+        Some(Err(e)) => {
+            eprintln!("Failed to read answers of day {day}: {e}");
+            None
+        }
+        None => None,
+    };
+
+    let mut record = PartRecord::new(
+        day,
+        part,
+        run.result.as_ref(),
+        expected,
+        &run.duration,
+        run.stats,
+    );
+    record.memory = run.memory;
+
+    if !options.quiet {
+        let _ = write_record(&mut stdout(), &record, options.format);
+    }
+
+    if let Some(result) = run.result {
+        if options.submit == Some(part) {
+            submit_result(result, year, day, part, options.format);
+        }
+    }
+
+    record
+}
+
+/// Runs the parse function of a solution, prints its timing in the configured format and returns the parsed input.
+/// The timing is returned as a [`PartRecord`] for part [`PARSE`].
+/// Returns [`None`] for the parsed input if parsing timed out or panicked.
+pub fn run_parse<T, F>(
+    func: F,
+    input: &Arc<str>,
+    day: Day,
+    options: &RunOptions,
+) -> (Option<T>, PartRecord)
+where
+    T: Send + 'static,
+    F: Fn(&str) -> T + Clone + Send + 'static,
+{
+    let run = run_timed(func, input, options, |_| {
+        if !options.quiet && options.format == OutputFormat::Human && options.bench.is_some() {
+            print!("Parse: > {ANSI_ITALIC}benching{ANSI_RESET}");
+            let _ = stdout().flush();
+        }
+    });
+
+    let run = match run {
+        Ok(run) => run,
+        Err(failure) => return (None, print_failure(day, PARSE, failure, options)),
+    };
+
+    let record = PartRecord {
+        day,
+        part: PARSE,
+        answer: None,
+        #[allow(clippy::cast_possible_truncation)]
+        duration_ns: run.duration.as_nanos() as u64,
+        samples: run.stats.as_ref().map_or(1, |x| x.samples),
+        status: Status::Parsed,
+        expected: None,
+        stats: run.stats,
+        memory: run.memory,
+        panic: None,
+    };
+
+    if !options.quiet {
+        let _ = write_record(&mut stdout(), &record, options.format);
+    }
+
+    (Some(run.result), record)
+}
+
+/// Creates and prints the record of a part that did not return a result.
+fn print_failure(day: Day, part: u8, failure: Failure, options: &RunOptions) -> PartRecord {
+    let (status, duration, panic) = match failure {
+        Failure::TimedOut => {
+            eprintln!(
+                "Warning: day {day} part {part} keeps running in the background, timings of later parts may be unreliable."
+            );
+            (Status::TimedOut, options.timeout.unwrap_or_default(), None)
+        }
+        Failure::Panicked(message) => (Status::Panicked, Duration::ZERO, Some(message)),
+    };
+
+    let record = PartRecord {
+        day,
+        part,
+        answer: None,
+        #[allow(clippy::cast_possible_truncation)]
+        duration_ns: duration.as_nanos() as u64,
+        samples: 0,
+        status,
+        expected: None,
+        stats: None,
+        memory: None,
+        panic,
+    };
+
+    if !options.quiet {
+        let _ = write_record(&mut stdout(), &record, options.format);
+    }
+
+    record
+}
+
+/// Part number of the record for the parse step of a solution.
+pub const PARSE: u8 = 0;
+
+/// Options that control how the parts of a solution are run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    /// Benchmark settings. Parts are only benched if this is set.
+    pub bench: Option<BenchConfig>,
+    pub format: OutputFormat,
+    /// The part whose answer is submitted.
+    pub submit: Option<u8>,
+    /// Don't print anything, results are only returned to the caller.
+    pub quiet: bool,
+    /// Compare answers with the accepted answers in `data/<year>/answers`.
+    pub verify: bool,
+    /// Maximum duration of the first execution of each part.
+    pub timeout: Option<Duration>,
+}
+
+impl RunOptions {
+    /// Reads the options passed to a solution binary: `--time`, `--budget <ms>`, `--warmup <n>`, `--format <format>`, `--timeout <duration>` and `--submit <part>`.
+    /// Answers are only verified when running against the puzzle input.
+    pub fn from_args() -> Result<Self, Error> {
+        let args: Vec<String> = env::args().collect();
+
+        let submit = match args.iter().position(|x| x == "--submit") {
+            Some(i) => match args.get(i + 1).map(|x| x.parse::<u8>()) {
+                Some(Ok(part)) => Some(part),
+                _ => {
+                    return Err(Error::Usage(
+                        "Unexpected command-line input. Format: cargo solve 1 --submit 1".into(),
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        // answers for other inputs can't be submitted or checked against accepted answers.
+        let is_puzzle_input = InputSource::from_args()? == InputSource::Puzzle;
+
+        if submit.is_some() && !is_puzzle_input {
+            return Err(Error::Usage(
+                "--submit can only be used when running against the puzzle input.".into(),
+            ));
+        }
+
+        let timeout = match args.iter().position(|x| x == "--timeout") {
+            Some(i) => args
+                .get(i + 1)
+                .map(|x| parse_duration(x))
+                .transpose()
+                .map_err(Error::Usage)?,
+            None => None,
+        };
+
+        Ok(Self {
+            bench: args
+                .iter()
+                .any(|x| x == "--time")
+                .then(BenchConfig::from_args),
+            format: OutputFormat::from_args(),
+            submit,
+            quiet: false,
+            verify: is_puzzle_input,
+            timeout,
+        })
+    }
+
+    /// Converts the options back to the arguments read by [`RunOptions::from_args`].
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![];
+
+        if let Some(part) = self.submit {
+            args.push("--submit".to_string());
+            args.push(part.to_string());
+        }
+
+        args.push("--format".to_string());
+        args.push(self.format.to_string());
+
+        if let Some(bench) = &self.bench {
+            args.push("--time".to_string());
+            args.append(&mut bench.to_args());
+        }
+
+        if let Some(timeout) = self.timeout {
+            args.push("--timeout".to_string());
+            args.push(format!("{}ms", timeout.as_millis()));
+        }
+
+        args
+    }
+}
+
+/// Output format of the runner, selected with `--format <human|json>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Formatted, colored output meant to be read in a terminal.
+    #[default]
+    Human,
+    /// One JSON-encoded [`PartRecord`] per line.
+    Json,
+}
+
+impl OutputFormat {
+    /// Reads the `--format` argument passed to a solution binary.
+    #[must_use]
+    pub fn from_args() -> Self {
+        let args: Vec<String> = env::args().collect();
+        args.iter()
+            .position(|x| x == "--format")
+            .and_then(|i| args.get(i + 1))
+            .and_then(|x| x.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = OutputFormatFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(OutputFormatFromStrError),
+        }
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Human => f.write_str("human"),
+            Self::Json => f.write_str("json"),
+        }
+    }
+}
+
+/// An error which can be returned when parsing an [`OutputFormat`].
+#[derive(Debug)]
+pub struct OutputFormatFromStrError;
+
+impl std::error::Error for OutputFormatFromStrError {}
+
+impl Display for OutputFormatFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting an output format of either \"human\" or \"json\"")
+    }
+}
+
+/// Outcome of running a solution part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    /// The part returned an answer and no accepted answer is recorded for it.
+    Solved,
+    /// The part returned the accepted answer.
+    Correct,
+    /// The part returned an answer that differs from the accepted answer.
+    Wrong,
+    /// The part returned [`None`].
+    Unsolved,
+    /// The input was parsed. Only used for the [`PARSE`] step.
+    Parsed,
+    /// The part did not finish within the timeout.
+    TimedOut,
+    /// The part panicked.
+    Panicked,
+}
+
+/// Structured result of running a solution part.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartRecord {
+    pub day: Day,
+    pub part: u8,
+    pub answer: Option<String>,
+    /// Execution time in nanoseconds. This is the median of all samples when benched.
+    pub duration_ns: u64,
+    pub samples: u128,
+    pub status: Status,
+    /// The accepted answer, if one is recorded in `data/<year>/answers`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// Detailed statistics, only present when benched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<BenchStats>,
+    /// Heap usage, only present with the `alloc_stats` feature.
+    #[serde(default, flatten, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<AllocStats>,
+    /// Message and location of the panic, only present if the part panicked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub panic: Option<String>,
+}
+
+impl PartRecord {
+    fn new<T: Display>(
+        day: Day,
+        part: u8,
+        result: Option<&T>,
+        expected: Option<String>,
+        duration: &Duration,
+        stats: Option<BenchStats>,
+    ) -> Self {
+        let answer = result.map(ToString::to_string);
+
+        let status = match (&answer, &expected) {
+            (None, _) => Status::Unsolved,
+            (Some(_), None) => Status::Solved,
+            (Some(answer), Some(expected)) if answer == expected => Status::Correct,
+            (Some(_), Some(_)) => Status::Wrong,
+        };
+
+        Self {
+            day,
+            part,
+            answer,
+            #[allow(clippy::cast_possible_truncation)]
+            duration_ns: duration.as_nanos() as u64,
+            samples: stats.as_ref().map_or(1, |x| x.samples),
+            status,
+            expected,
+            stats,
+            memory: None,
+            panic: None,
+        }
+    }
+
+    /// Whether the part did not finish within the timeout.
+    #[must_use]
+    pub fn is_timed_out(&self) -> bool {
+        self.status == Status::TimedOut
+    }
+
+    /// Whether the part panicked.
+    #[must_use]
+    pub fn is_panicked(&self) -> bool {
+        self.status == Status::Panicked
+    }
+
+    /// Whether the part no longer returns its accepted answer.
+    #[must_use]
+    pub fn is_regression(&self) -> bool {
+        self.expected.is_some() && self.status != Status::Correct
+    }
+
+    /// Parses a record from a line of `--format json` output.
+    /// Returns [`None`] for lines that are not records, e.g. output of aoc-cli.
+    #[must_use]
+    pub fn from_json(line: &str) -> Option<Self> {
+        serde_json::from_str(line).ok()
+    }
+
+    /// Serializes the record to a single line of JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("records are always serializable")
+    }
+
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        Duration::from_nanos(self.duration_ns)
+    }
+}
+
+/// Writes a [`PartRecord`] to `out` in the given format.
+pub fn write_record(
+    out: &mut impl Write,
+    record: &PartRecord,
+    format: OutputFormat,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Human if record.is_timed_out() || record.is_panicked() => {
+            let label = if record.part == PARSE {
+                "Parse".to_string()
+            } else {
+                format!("Part {}", record.part)
+            };
+
+            match &record.panic {
+                Some(message) => writeln!(out, "\r{label}: ✖ panicked: {message}"),
+                None => writeln!(
+                    out,
+                    "\r{label}: ✖ timed out after {:.1?}",
+                    record.duration()
+                ),
+            }
+        }
+        OutputFormat::Human if record.part == PARSE => {
+            writeln!(
+                out,
+                "\rParse:{}{}",
+                format_duration(&record.duration(), record.stats.as_ref()),
+                format_memory(record.memory.as_ref())
+            )?;
+
+            if let Some(stats) = &record.stats {
+                writeln!(out, "{}", format_stats(stats))?;
+            }
+
+            Ok(())
+        }
+        OutputFormat::Human => {
+            let verdict = match (&record.status, &record.expected) {
+                (Status::Correct, _) => " ✔".to_string(),
+                (_, Some(expected)) => format!(" ✘ expected {expected}"),
+                _ => String::new(),
+            };
+
+            write_result(
+                out,
+                &record.answer,
+                &format!("Part {}", record.part),
+                &format!(
+                    "{verdict}{}{}",
+                    format_duration(&record.duration(), record.stats.as_ref()),
+                    format_memory(record.memory.as_ref())
+                ),
+            )?;
+
+            if let Some(stats) = &record.stats {
+                writeln!(out, "{}", format_stats(stats))?;
+            }
+
+            Ok(())
+        }
+        OutputFormat::Json => writeln!(out, "{}", record.to_json()),
+    }
+}
+
+/// Settings that control how a solution part is benchmarked when running with `--time`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Iterations that are executed before measuring. Their timings are discarded.
+    pub warmup: u32,
+    /// Approximate amount of time that is spent measuring a part.
+    pub budget: Duration,
+    /// Lower bound for the number of measured samples.
+    pub min_samples: u128,
+    /// Upper bound for the number of measured samples.
+    pub max_samples: u128,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            budget: Duration::from_secs(1),
+            min_samples: 10,
+            max_samples: 10000,
+        }
+    }
+}
+
+impl BenchConfig {
+    /// Reads the `--budget <ms>` and `--warmup <n>` arguments passed to a solution binary.
+    /// Values that are missing or can't be parsed fall back to the defaults.
+    #[must_use]
+    pub fn from_args() -> Self {
+        let args: Vec<String> = env::args().collect();
+        let value_of = |flag: &str| {
+            args.iter()
+                .position(|x| x == flag)
+                .and_then(|i| args.get(i + 1))
+        };
+
+        Self::with_overrides(
+            value_of("--budget").and_then(|x| x.parse().ok()),
+            value_of("--warmup").and_then(|x| x.parse().ok()),
+        )
+    }
+
+    /// Converts the config back to the arguments read by [`BenchConfig::from_args`].
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        vec![
+            "--budget".into(),
+            self.budget.as_millis().to_string(),
+            "--warmup".into(),
+            self.warmup.to_string(),
+        ]
+    }
+
+    /// Creates a config from the defaults, replacing the budget (in milliseconds) and warm-up iterations if provided.
+    #[must_use]
+    pub fn with_overrides(budget: Option<u64>, warmup: Option<u32>) -> Self {
+        let mut config = Self::default();
+
+        if let Some(budget) = budget {
+            config.budget = Duration::from_millis(budget);
+        }
+
+        if let Some(warmup) = warmup {
+            config.warmup = warmup;
+        }
+
+        config
+    }
+
+    /// Number of measured iterations for a function whose first execution took `base_time`.
+    fn iterations(&self, base_time: &Duration) -> u128 {
+        (self.budget.as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(
+            cmp::max(self.min_samples, 1),
+            cmp::max(self.min_samples, self.max_samples),
+        )
+    }
+}
+
+/// Summary statistics of a benchmarked solution part.
+/// Outliers are rejected before any of the durations are computed.
+/// Durations serialize as integer nanoseconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchStats {
+    /// Number of measured samples, including rejected outliers.
+    pub samples: u128,
+    /// Number of samples that were rejected as outliers.
+    pub outliers: usize,
+    #[serde(rename = "mean_ns", with = "duration_nanos")]
+    pub mean: Duration,
+    #[serde(rename = "min_ns", with = "duration_nanos")]
+    pub min: Duration,
+    #[serde(rename = "median_ns", with = "duration_nanos")]
+    pub median: Duration,
+    #[serde(rename = "p95_ns", with = "duration_nanos")]
+    pub p95: Duration,
+    #[serde(rename = "std_dev_ns", with = "duration_nanos")]
+    pub std_dev: Duration,
+    /// Average timings of the spans entered by the part, see [`span!`](crate::span).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spans: Vec<SpanStats>,
+}
+
+pub(crate) mod duration_nanos {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_nanos() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_nanos)
+    }
+}
+
+impl BenchStats {
+    /// Computes statistics for a set of timings.
+    /// Samples outside of Tukey's fences (1.5 times the interquartile range) are treated as outliers.
+    /// Returns [`None`] if there are no timings.
+    #[must_use]
+    pub fn from_timings(timings: &[Duration]) -> Option<Self> {
+        if timings.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<u128> = timings.iter().map(Duration::as_nanos).collect();
+        sorted.sort_unstable();
+
+        let q1 = percentile(&sorted, 25.0);
+        let q3 = percentile(&sorted, 75.0);
+        let fence = (q3 - q1) * 3 / 2;
+        let (lower, upper) = (q1.saturating_sub(fence), q3 + fence);
+
+        let kept: Vec<u128> = sorted
+            .iter()
+            .copied()
+            .filter(|x| (lower..=upper).contains(x))
+            .collect();
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+        let mean = kept.iter().sum::<u128>() / kept.len() as u128;
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+        #[allow(clippy::cast_precision_loss)]
+        let variance = kept
+            .iter()
+            .map(|&x| (x as f64 - mean as f64).powi(2))
+            .sum::<f64>()
+            / kept.len() as f64;
+
+        Some(Self {
+            samples: sorted.len() as u128,
+            outliers: sorted.len() - kept.len(),
+            mean: nanos_to_duration(mean),
+            min: nanos_to_duration(kept[0]),
+            median: nanos_to_duration(percentile(&kept, 50.0)),
+            p95: nanos_to_duration(percentile(&kept, 95.0)),
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            std_dev: Duration::from_nanos(variance.sqrt().round() as u64),
+            spans: vec![],
+        })
     }
 }
 
+/// Nearest-rank percentile of a sorted, non-empty slice.
+fn percentile(sorted: &[u128], p: f64) -> u128 {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn nanos_to_duration(nanos: u128) -> Duration {
+    Duration::from_nanos(nanos as u64)
+}
+
+/// Reason why [`run_timed`] did not return a result.
+enum Failure {
+    TimedOut,
+    /// Message and location of the panic.
+    Panicked(String),
+}
+
+/// Result of running a function with [`run_timed`].
+struct Run<T> {
+    result: T,
+    duration: Duration,
+    stats: Option<BenchStats>,
+    memory: Option<AllocStats>,
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
-    func: impl Fn(I) -> T,
-    input: I,
+///  2. in release, the function is benched according to the [`BenchConfig`] passed on the command-line with `--time`
+///     (by default, a few warm-up iterations followed by approx. 1 second of execution time or 10 samples, whatever takes longer.)
+///
+/// Heap usage is measured during the first execution if the `alloc_stats` feature is enabled.
+/// Returns a [`Failure`] if the first execution exceeds the timeout or panics, the function is not benched in that case.
+/// The input is shared through an [`Arc`], since the first execution runs on a separate thread when a timeout is set.
+///
+/// With a timeout, benching stops once the timeout has elapsed, and a function whose first execution took more than
+/// half of the timeout is not benched at all.
+fn run_timed<I, T, F>(
+    func: F,
+    input: &Arc<I>,
+    options: &RunOptions,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
-    let timer = Instant::now();
-    let result = func(input.clone());
-    let base_time = timer.elapsed();
+) -> Result<Run<T>, Failure>
+where
+    I: ?Sized + Send + Sync + 'static,
+    T: Send + 'static,
+    F: Fn(&I) -> T + Clone + Send + 'static,
+{
+    let first_run = {
+        let func = func.clone();
+        let input = Arc::clone(input);
+        move || {
+            panics::catch(|| {
+                alloc::measure(|| {
+                    let timer = Instant::now();
+                    let result = func(&input);
+                    (result, timer.elapsed())
+                })
+            })
+        }
+    };
+
+    let first_result = match options.timeout {
+        Some(timeout) => run_with_timeout(first_run, timeout).ok_or(Failure::TimedOut)?,
+        None => first_run(),
+    };
+
+    let ((result, base_time), memory) = first_result.map_err(Failure::Panicked)?;
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
-    } else {
-        (base_time, 1)
+    let is_near_timeout = options
+        .timeout
+        .is_some_and(|timeout| base_time > timeout / 2);
+
+    let (duration, stats) = match &options.bench {
+        Some(config) if !is_near_timeout => {
+            let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+            let stats = bench(func, &**input, &base_time, config, deadline);
+            (stats.median, Some(stats))
+        }
+        _ => (base_time, None),
     };
 
-    (result, run.0, run.1)
+    Ok(Run {
+        result,
+        duration,
+        stats,
+        memory,
+    })
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
-    let mut stdout = stdout();
+/// Runs `func` on a separate thread and waits at most `timeout` for it to return.
+/// Returns [`None`] on timeout, the thread keeps running in the background until the process exits.
+fn run_with_timeout<T: Send + 'static>(
+    func: impl FnOnce() -> T + Send + 'static,
+    timeout: Duration,
+) -> Option<T> {
+    let (sender, receiver) = mpsc::channel();
 
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+    let handle = thread::spawn(move || {
+        let _ = sender.send(func());
+    });
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => Some(result),
+        Err(RecvTimeoutError::Timeout) => None,
+        // the sender was dropped without sending, i.e. `func` panicked.
+        Err(RecvTimeoutError::Disconnected) => match handle.join() {
+            Err(payload) => panic::resume_unwind(payload),
+            Ok(()) => unreachable!("the result is sent before the thread finishes"),
+        },
+    }
+}
 
-    let mut timers: Vec<Duration> = vec![];
+/// Parses a duration such as `500ms`, `10s` or `2m`. Plain numbers are interpreted as seconds.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let error = || format!("invalid duration \"{s}\", expecting a value like 500ms, 10s or 2m");
 
-    for _ in 0..bench_iterations {
-        // need a clone here to make the borrow checker happy.
-        let cloned = input.clone();
-        let timer = Instant::now();
-        func(cloned);
-        timers.push(timer.elapsed());
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: f64 = value.parse().map_err(|_| error())?;
+
+    let seconds = match unit {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        _ => return Err(error()),
+    };
+
+    Duration::try_from_secs_f64(seconds).map_err(|_| error())
+}
+
+/// Benches `func` according to `config`. Warm-up and measured iterations stop early once `deadline` has passed,
+/// but at least one sample is measured.
+fn bench<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: &Duration,
+    config: &BenchConfig,
+    deadline: Option<Instant>,
+) -> BenchStats {
+    let bench_iterations = config.iterations(base_time);
+    let is_past_deadline = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+
+    for _ in 0..config.warmup {
+        if is_past_deadline() {
+            break;
+        }
+        func(input.clone());
     }
 
-    (
-        #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+    let (timers, spans) = spans::record(|| {
+        let mut timers: Vec<Duration> = vec![];
+
+        for _ in 0..bench_iterations {
+            if !timers.is_empty() && is_past_deadline() {
+                break;
+            }
+            // need a clone here to make the borrow checker happy.
+            let cloned = input.clone();
+            let timer = Instant::now();
+            func(cloned);
+            timers.push(timer.elapsed());
+        }
+
+        let runs = timers.len() as u128;
+        (timers, runs)
+    });
+
+    // NOTE: `BenchConfig::iterations` always measures at least one sample.
+    let stats = BenchStats::from_timings(&timers).expect("at least one sample was measured");
+    BenchStats { spans, ..stats }
 }
 
-fn average_duration(numbers: &[Duration]) -> u128 {
-    numbers
-        .iter()
-        .map(std::time::Duration::as_nanos)
-        .sum::<u128>()
-        / numbers.len() as u128
+/// The headline duration is the median of all samples when benched.
+fn format_duration(duration: &Duration, stats: Option<&BenchStats>) -> String {
+    match stats {
+        Some(stats) => format!(" ({duration:.1?} @ {} samples)", stats.samples),
+        None => format!(" ({duration:.1?})"),
+    }
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
-    if samples == 1 {
-        format!(" ({duration:.1?})")
-    } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
+fn format_memory(memory: Option<&AllocStats>) -> String {
+    match memory {
+        Some(memory) => format!(
+            " · {} peak, {} allocations",
+            alloc::format_bytes(memory.peak_bytes),
+            memory.allocations
+        ),
+        None => String::new(),
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
-    let is_intermediate_result = duration_str.is_empty();
+fn format_stats(stats: &BenchStats) -> String {
+    let mut lines = vec![format!(
+        "        {ANSI_ITALIC}min {:.1?} · p95 {:.1?} · mean {:.1?} · σ {:.1?} · {} outlier(s) rejected{ANSI_RESET}",
+        stats.min, stats.p95, stats.mean, stats.std_dev, stats.outliers
+    )];
+
+    lines.extend(format_spans(&stats.spans));
+    lines.join("\n")
+}
+
+/// Formats spans as a tree, nested spans are indented below their parent.
+fn format_spans(spans: &[SpanStats]) -> Vec<String> {
+    let labels: Vec<String> = spans
+        .iter()
+        .map(|span| {
+            let indent = "  ".repeat(span.path.len() - 1);
+            let name = span.path.last().map_or("", String::as_str);
+            format!("{indent}{name}")
+        })
+        .collect();
+
+    let width = labels.iter().map(|x| x.chars().count()).max().unwrap_or(0);
+
+    spans
+        .iter()
+        .zip(labels)
+        .map(|(span, label)| {
+            let calls = if span.calls > 1 {
+                format!(" · {} calls", span.calls)
+            } else {
+                String::new()
+            };
+            format!(
+                "          {ANSI_ITALIC}{label:<width$}  {:.1?}{calls}{ANSI_RESET}",
+                span.mean
+            )
+        })
+        .collect()
+}
+
+fn write_result<T: Display>(
+    out: &mut impl Write,
+    result: &Option<T>,
+    part: &str,
+    suffix: &str,
+) -> io::Result<()> {
+    let is_intermediate_result = suffix.is_empty();
 
     match result {
         Some(result) => {
             if result.to_string().contains('\n') {
-                let str = format!("{part}: ▼ {duration_str}");
+                let str = format!("{part}: ▼ {suffix}");
                 if is_intermediate_result {
-                    print!("{str}");
+                    write!(out, "{str}")?;
                 } else {
-                    print!("\r");
-                    println!("{str}");
-                    println!("{result}");
+                    write!(out, "\r")?;
+                    writeln!(out, "{str}")?;
+                    writeln!(out, "{result}")?;
                 }
             } else {
-                let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
+                let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{suffix}");
                 if is_intermediate_result {
-                    print!("{str}");
+                    write!(out, "{str}")?;
                 } else {
-                    print!("\r");
-                    println!("{str}");
+                    write!(out, "\r")?;
+                    writeln!(out, "{str}")?;
                 }
             }
         }
         None => {
             if is_intermediate_result {
-                print!("{part}: ✖");
+                write!(out, "{part}: ✖")?;
             } else {
-                print!("\r");
-                println!("{part}: ✖             ");
+                write!(out, "\r")?;
+                writeln!(out, "{part}: ✖             ")?;
             }
         }
     }
+
+    Ok(())
 }
 
-/// Parse the arguments passed to `solve` and try to submit one part of the solution if:
-///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
-    day: Day,
-    part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
-    let args: Vec<String> = env::args().collect();
+/// Submits one part of the solution via aoc-cli, which is checked for before running the parts.
+/// Answers that the submission ledger knows to be wrong are not submitted, and the verdict is added to the ledger.
+/// A correct answer is saved as the accepted answer of the part, which earns the part its star.
+/// With JSON output, all of this is reported on stderr.
+fn submit_result<T: Display>(result: T, year: Year, day: Day, part: u8, format: OutputFormat) {
+    let answer = result.to_string();
+    // keep stdout machine-readable.
+    let mut out: Box<dyn Write> = match format {
+        OutputFormat::Human => Box::new(stdout()),
+        OutputFormat::Json => Box::new(io::stderr()),
+    };
+
+    let ledger = match submissions::read(year) {
+        Ok(ledger) => ledger,
+        Err(e) => {
+            eprintln!("Not submitting {answer}, failed to read the submissions: {e}");
+            return;
+        }
+    };
+
+    let answers = match answers::read(year, day) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("Not submitting {answer}, failed to read answers of day {day}: {e}");
+            return;
+        }
+    };
 
-    if !args.contains(&"--submit".into()) {
-        return None;
+    let now = SystemTime::now();
+    if let Err(refusal) = submissions::check(&ledger, answers.as_ref(), day, part, &answer, now) {
+        eprintln!("Not submitting {answer}: {refusal}.");
+        return;
     }
 
-    if args.len() < 3 {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
+    let _ = writeln!(out, "Submitting result via aoc-cli...");
+    let output = match aoc_cli::submit(year, day, part, &answer) {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("failed to call aoc-cli: {e}");
+            return;
+        }
+    };
+
+    let response = String::from_utf8_lossy(&output.stdout);
+    let _ = write!(out, "{response}");
+
+    let Some(submission) = Submission::new(day, part, &answer, &response, SystemTime::now()) else {
+        eprintln!("Could not read the verdict from the response, the submission is not recorded.");
+        return;
+    };
+
+    if let Err(e) = submissions::append(year, &submission) {
+        eprintln!("Failed to record the submission: {e}");
     }
 
-    let part_index = args.iter().position(|x| x == "--submit").unwrap() + 1;
+    if submission.verdict == Verdict::Correct {
+        match answers::save(year, day, part, &answer) {
+            Ok(()) => {
+                let _ = writeln!(
+                    out,
+                    "★ Saved {answer} as the accepted answer in \"{}\".",
+                    answers::get_path(year, day)
+                );
+            }
+            Err(e) => eprintln!("Failed to save the accepted answer: {e}"),
+        }
+    }
+}
 
-    let Ok(part_submit) = args[part_index].parse::<u8>() else {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        bench, format_spans, parse_duration, run_with_timeout, BenchConfig, BenchStats, PartRecord,
+        Status,
     };
+    use crate::day;
+    use crate::template::alloc::AllocStats;
+    use crate::template::spans::SpanStats;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn nanos(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&x| Duration::from_nanos(x)).collect()
+    }
+
+    #[test]
+    fn formats_nested_spans() {
+        let span = |path: &[&str], nanos: u64, calls: u128| SpanStats {
+            path: path.iter().map(ToString::to_string).collect(),
+            mean: Duration::from_nanos(nanos),
+            calls,
+        };
+
+        let lines = format_spans(&[
+            span(&["parse"], 2000, 1),
+            span(&["parse", "line"], 500, 4),
+            span(&["solve"], 1000, 1),
+        ]);
+
+        assert!(lines[0].contains("parse   2.0µs"));
+        assert!(lines[1].contains("  line  500.0ns · 4 calls"));
+        assert!(lines[2].contains("solve   1.0µs"));
+    }
+
+    #[test]
+    fn stats_of_empty_timings() {
+        assert_eq!(BenchStats::from_timings(&[]), None);
+    }
+
+    #[test]
+    fn stats_of_single_timing() {
+        let stats = BenchStats::from_timings(&nanos(&[42])).unwrap();
+        assert_eq!(stats.samples, 1);
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(stats.min, Duration::from_nanos(42));
+        assert_eq!(stats.median, Duration::from_nanos(42));
+        assert_eq!(stats.p95, Duration::from_nanos(42));
+        assert_eq!(stats.std_dev, Duration::ZERO);
+    }
+
+    #[test]
+    fn stats_reject_outliers() {
+        let stats =
+            BenchStats::from_timings(&nanos(&[10, 12, 11, 10, 13, 12, 11, 10, 12, 5000])).unwrap();
+        assert_eq!(stats.samples, 10);
+        assert_eq!(stats.outliers, 1);
+        assert_eq!(stats.min, Duration::from_nanos(10));
+        assert_eq!(stats.median, Duration::from_nanos(11));
+        assert_eq!(stats.p95, Duration::from_nanos(13));
+        assert_eq!(stats.mean, Duration::from_nanos(11));
+        assert_eq!(stats.std_dev, Duration::from_nanos(1));
+    }
+
+    #[test]
+    fn iterations_respect_bounds() {
+        let config = BenchConfig::default();
+        assert_eq!(config.iterations(&Duration::from_secs(5)), 10);
+        assert_eq!(config.iterations(&Duration::from_millis(10)), 100);
+        assert_eq!(config.iterations(&Duration::from_nanos(1)), 10000);
+    }
+
+    #[test]
+    fn iterations_use_budget() {
+        let config = BenchConfig {
+            budget: Duration::from_millis(100),
+            ..BenchConfig::default()
+        };
+        assert_eq!(config.iterations(&Duration::from_micros(100)), 1000);
+    }
 
-    if part_submit != part {
-        return None;
+    #[test]
+    fn record_json_roundtrip() {
+        let stats = BenchStats::from_timings(&nanos(&[10, 12, 11])).unwrap();
+        let median = stats.median;
+        let record = PartRecord::new(
+            day!(3),
+            2,
+            Some(&"a (b) @ 1 samples)"),
+            None,
+            &median,
+            Some(stats),
+        );
+        let json = record.to_json();
+        assert!(json.contains("\"duration_ns\":11"));
+        assert!(json.contains("\"status\":\"solved\""));
+        assert_eq!(PartRecord::from_json(&json), Some(record));
     }
 
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+    #[test]
+    fn record_of_unsolved_part() {
+        let record = PartRecord::new::<u32>(day!(1), 1, None, None, &Duration::from_nanos(5), None);
+        assert_eq!(record.status, Status::Unsolved);
+        assert_eq!(record.samples, 1);
+        assert_eq!(
+            record.to_json(),
+            r#"{"day":1,"part":1,"answer":null,"duration_ns":5,"samples":1,"status":"unsolved"}"#
+        );
     }
 
-    println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    #[test]
+    fn ignores_non_record_lines() {
+        assert_eq!(
+            PartRecord::from_json("Submitting result via aoc-cli..."),
+            None
+        );
+    }
+
+    #[test]
+    fn record_verifies_expected_answer() {
+        let duration = Duration::from_nanos(5);
+
+        let correct = PartRecord::new(day!(1), 1, Some(&42), Some("42".into()), &duration, None);
+        assert_eq!(correct.status, Status::Correct);
+        assert!(!correct.is_regression());
+
+        let wrong = PartRecord::new(day!(1), 1, Some(&41), Some("42".into()), &duration, None);
+        assert_eq!(wrong.status, Status::Wrong);
+        assert!(wrong.is_regression());
+
+        let unsolved = PartRecord::new::<u32>(day!(1), 1, None, Some("42".into()), &duration, None);
+        assert_eq!(unsolved.status, Status::Unsolved);
+        assert!(unsolved.is_regression());
+
+        let unknown = PartRecord::new(day!(1), 1, Some(&42), None, &duration, None);
+        assert_eq!(unknown.status, Status::Solved);
+        assert!(!unknown.is_regression());
+    }
+
+    #[test]
+    fn record_json_with_memory() {
+        let mut record =
+            PartRecord::new(day!(1), 1, Some(&1), None, &Duration::from_nanos(5), None);
+        record.memory = Some(AllocStats {
+            peak_bytes: 2048,
+            allocations: 3,
+        });
+        let json = record.to_json();
+        assert!(json.ends_with(r#""status":"solved","peak_bytes":2048,"allocations":3}"#));
+        assert_eq!(PartRecord::from_json(&json), Some(record));
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("10s"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("10h").is_err());
+        assert!(parse_duration("-1s").is_err());
+    }
+
+    #[test]
+    fn times_out_slow_functions() {
+        let slow = || thread::sleep(Duration::from_secs(5));
+        assert_eq!(run_with_timeout(slow, Duration::from_millis(10)), None);
+        assert_eq!(run_with_timeout(|| 42, Duration::from_secs(5)), Some(42));
+    }
+
+    #[test]
+    fn stops_benching_at_deadline() {
+        let slow = |()| thread::sleep(Duration::from_millis(5));
+        let config = BenchConfig::default();
+        let deadline = Instant::now() + Duration::from_millis(20);
+
+        let stats = bench(slow, (), &Duration::from_millis(5), &config, Some(deadline));
+        assert!(stats.samples >= 1 && stats.samples < config.min_samples);
+
+        let stats = bench(
+            slow,
+            (),
+            &Duration::from_millis(5),
+            &config,
+            Some(Instant::now()),
+        );
+        assert_eq!(stats.samples, 1);
+    }
 }
@@ -0,0 +1,21 @@
+/// Common interface of all solutions, used to run them without spawning their binaries.
+use crate::template::runner::{PartRecord, RunOptions};
+use crate::{Day, Year};
+
+/// A solution for a day of advent. This is implemented by the [`solution!`](crate::solution) macro.
+pub trait Solution: Sync {
+    /// The year of the day this solution belongs to.
+    fn year(&self) -> Year;
+
+    /// The day this solution belongs to.
+    fn day(&self) -> Day;
+
+    /// The number of puzzle parts of this day, `1` for single-part days.
+    fn parts(&self) -> u8;
+
+    /// Runs every part of the solution against `input` and returns their results.
+    fn run(&self, input: &str, options: &RunOptions) -> Vec<PartRecord>;
+}
+
+/// All solutions that are compiled into the main binary, ordered by year and day.
+pub type Registry = [&'static dyn Solution];
@@ -0,0 +1,227 @@
+/// Module that times phases inside a solution part, e.g. parsing versus searching.
+/// Spans are only recorded while a part is benched with `--time`. Otherwise, entering a span
+/// only checks a thread-local flag, so they can be left in solutions.
+///
+/// Spans are recorded on the thread that runs the part, spans entered on other threads are ignored.
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::template::runner::duration_nanos;
+
+thread_local! {
+    static IS_RECORDING: Cell<bool> = const { Cell::new(false) };
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+#[derive(Default)]
+struct State {
+    nodes: Vec<Node>,
+    /// Nodes of the outermost spans.
+    roots: Vec<usize>,
+    /// Node and start time of each entered span, innermost last.
+    stack: Vec<(usize, Instant)>,
+}
+
+struct Node {
+    name: &'static str,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    total: Duration,
+    calls: u128,
+}
+
+/// Average timing of a span over all benched runs of a part.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpanStats {
+    /// Names of the enclosing spans and of the span itself, outermost first.
+    pub path: Vec<String>,
+    /// Time spent in the span per run of the part.
+    #[serde(rename = "mean_ns", with = "duration_nanos")]
+    pub mean: Duration,
+    /// Number of times the span was entered per run of the part.
+    pub calls: u128,
+}
+
+/// Times the enclosing scope as a span of the solution part that is running. Created with [`span!`](crate::span).
+#[must_use = "a span is timed until it is dropped, bind it with `let _span = span!(...)`"]
+pub struct Span {
+    is_recording: bool,
+}
+
+impl Span {
+    #[inline]
+    pub fn enter(name: &'static str) -> Self {
+        if !IS_RECORDING.get() {
+            return Self {
+                is_recording: false,
+            };
+        }
+
+        STATE.with_borrow_mut(|state| {
+            let parent = state.stack.last().map(|&(parent, _)| parent);
+            let siblings = match parent {
+                Some(parent) => &state.nodes[parent].children,
+                None => &state.roots,
+            };
+
+            let index = match siblings.iter().find(|&&x| state.nodes[x].name == name) {
+                Some(&index) => index,
+                None => {
+                    let index = state.nodes.len();
+                    state.nodes.push(Node {
+                        name,
+                        parent,
+                        children: vec![],
+                        total: Duration::ZERO,
+                        calls: 0,
+                    });
+                    match parent {
+                        Some(parent) => state.nodes[parent].children.push(index),
+                        None => state.roots.push(index),
+                    }
+                    index
+                }
+            };
+
+            state.stack.push((index, Instant::now()));
+        });
+
+        Self { is_recording: true }
+    }
+}
+
+impl Drop for Span {
+    #[inline]
+    fn drop(&mut self) {
+        if !self.is_recording {
+            return;
+        }
+
+        let end = Instant::now();
+
+        STATE.with_borrow_mut(|state| {
+            if let Some((index, start)) = state.stack.pop() {
+                let node = &mut state.nodes[index];
+                node.total += end - start;
+                node.calls += 1;
+            }
+        });
+    }
+}
+
+/// Records the spans entered by `func`, which runs a part and returns its result with the number of runs.
+/// Spans are returned depth-first: each span is followed by its children, in the order they were first entered.
+pub(crate) fn record<T>(func: impl FnOnce() -> (T, u128)) -> (T, Vec<SpanStats>) {
+    STATE.set(State::default());
+    IS_RECORDING.set(true);
+    let (result, runs) = func();
+    IS_RECORDING.set(false);
+
+    let runs = runs.max(1);
+    let State { nodes, roots, .. } = STATE.take();
+
+    let mut spans = vec![];
+    let mut pending: Vec<usize> = roots.into_iter().rev().collect();
+    while let Some(index) = pending.pop() {
+        let node = &nodes[index];
+        spans.push(SpanStats {
+            path: get_path(&nodes, node),
+            #[allow(clippy::cast_possible_truncation)]
+            mean: Duration::from_nanos((node.total.as_nanos() / runs) as u64),
+            calls: node.calls / runs,
+        });
+        pending.extend(node.children.iter().rev());
+    }
+
+    (result, spans)
+}
+
+/// Names of the enclosing spans of `node` and of the node itself, outermost first.
+fn get_path(nodes: &[Node], node: &Node) -> Vec<String> {
+    let mut path = vec![node.name.to_string()];
+    let mut parent = node.parent;
+    while let Some(index) = parent {
+        path.push(nodes[index].name.to_string());
+        parent = nodes[index].parent;
+    }
+    path.reverse();
+    path
+}
+
+/// Times the rest of the enclosing scope as a named phase of a solution part.
+/// With `--time`, the average time spent in each span is printed below the timing of the part.
+/// Spans can be nested, nested spans are printed below their parent.
+///
+/// ```ignore
+/// pub fn part_one(input: &str) -> Option<u64> {
+///     let seeds = {
+///         let _span = advent_of_code::span!("parse");
+///         parse(input)
+///     };
+///
+///     let _span = advent_of_code::span!("fold ranges");
+///     seeds.iter().map(fold).min()
+/// }
+/// ```
+#[macro_export]
+macro_rules! span {
+    ($name:expr) => {
+        $crate::template::spans::Span::enter($name)
+    };
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{record, Span};
+
+    fn names(path: &[String]) -> Vec<&str> {
+        path.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn ignores_spans_when_not_recording() {
+        let _span = Span::enter("parse");
+        let ((), spans) = record(|| ((), 1));
+        assert!(spans.is_empty());
+    }
+
+    #[test]
+    fn records_nested_spans() {
+        let ((), spans) = record(|| {
+            for _ in 0..2 {
+                let _outer = Span::enter("parse");
+                for _ in 0..3 {
+                    let _inner = Span::enter("line");
+                }
+            }
+            let _other = Span::enter("solve");
+            let _inner = Span::enter("line");
+            ((), 2)
+        });
+
+        assert_eq!(spans.len(), 4);
+        assert_eq!(names(&spans[0].path), ["parse"]);
+        assert_eq!(spans[0].calls, 1);
+        assert_eq!(names(&spans[1].path), ["parse", "line"]);
+        assert_eq!(spans[1].calls, 3);
+        assert_eq!(names(&spans[2].path), ["solve"]);
+        assert_eq!(names(&spans[3].path), ["solve", "line"]);
+        assert!(spans[1].mean <= spans[0].mean);
+    }
+
+    #[test]
+    fn lists_children_below_their_parent() {
+        let ((), spans) = record(|| {
+            drop(Span::enter("a"));
+            drop(Span::enter("b"));
+            let _a = Span::enter("a");
+            let _c = Span::enter("c");
+            ((), 1)
+        });
+
+        let paths: Vec<Vec<&str>> = spans.iter().map(|x| names(&x.path)).collect();
+        assert_eq!(paths, [vec!["a"], vec!["a", "c"], vec!["b"]]);
+    }
+}
@@ -0,0 +1,95 @@
+/// Module that tells which stars were earned on each day.
+/// A part has a star if its accepted answer is recorded in `data/<year>/answers`, or if the submission ledger
+/// knows it to be solved, e.g. because the website rejected another answer for it as already solved.
+use std::fmt::Display;
+
+use crate::template::answers::{self, Answers};
+use crate::template::submissions::{self, Submission};
+use crate::template::Error;
+use crate::{Day, Year};
+
+/// The stars earned on a day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stars {
+    pub part_one: bool,
+    pub part_two: bool,
+}
+
+impl Stars {
+    /// Combines the accepted `answers` of `day` with the verdicts of the `ledger`.
+    #[must_use]
+    pub fn new(answers: Option<&Answers>, ledger: &[Submission], day: Day) -> Self {
+        Self {
+            part_one: submissions::is_solved(ledger, answers, day, 1),
+            part_two: submissions::is_solved(ledger, answers, day, 2),
+        }
+    }
+
+    /// Whether `part` has a star.
+    #[must_use]
+    pub fn has(self, part: u8) -> bool {
+        match part {
+            1 => self.part_one,
+            2 => self.part_two,
+            _ => false,
+        }
+    }
+
+    #[must_use]
+    pub fn count(self) -> usize {
+        usize::from(self.part_one) + usize::from(self.part_two)
+    }
+}
+
+impl Display for Stars {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for part in [1, 2] {
+            write!(f, "{}", if self.has(part) { '★' } else { '☆' })?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the stars earned on `day`.
+pub fn read(year: Year, day: Day) -> Result<Stars, Error> {
+    let answers = answers::read(year, day)?;
+    let ledger = submissions::read(year)?;
+    Ok(Stars::new(answers.as_ref(), &ledger, day))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Stars;
+    use crate::day;
+    use crate::template::answers::Answers;
+    use crate::template::submissions::Submission;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn combines_answers_and_ledger() {
+        let answers = Answers::parse("part_one = 142").unwrap();
+        let ledger = [
+            Submission::new(day!(1), 1, "142", "That's the right answer!", UNIX_EPOCH).unwrap(),
+            Submission::new(
+                day!(1),
+                2,
+                "281",
+                "You don't seem to be solving the right level.",
+                UNIX_EPOCH + Duration::from_secs(60),
+            )
+            .unwrap(),
+            Submission::new(day!(2), 1, "8", "That's the right answer!", UNIX_EPOCH).unwrap(),
+        ];
+
+        let stars = Stars::new(Some(&answers), &ledger, day!(1));
+        assert_eq!(stars.to_string(), "★★");
+        assert_eq!(stars.count(), 2);
+
+        let stars = Stars::new(None, &ledger, day!(2));
+        assert_eq!(stars.to_string(), "★☆");
+
+        let stars = Stars::new(None, &ledger, day!(3));
+        assert_eq!(stars.to_string(), "☆☆");
+        assert!(!stars.has(1));
+    }
+}
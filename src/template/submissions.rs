@@ -0,0 +1,577 @@
+/// Module that keeps a ledger of the answers submitted for each day in `data/<year>/submissions.jsonl`.
+/// The ledger is used to refuse submissions that can only be wrong, and to wait out the cooldown of the website:
+/// answers that were submitted before, answers outside the bounds of earlier "too high" and "too low" verdicts,
+/// and any answer while the last verdict asked to wait.
+use std::fmt::Display;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::template::answers::Answers;
+use crate::template::Error;
+use crate::{Day, Year};
+
+/// The verdict of the website on a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "verdict", rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    Incorrect {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        hint: Option<Hint>,
+    },
+    /// The part was already solved, the answer was not checked.
+    /// The website says the same for part two while part one is unsolved, see [`is_solved`].
+    AlreadySolved,
+    /// The answer was not checked, since the last submission was too recent.
+    TooSoon {
+        #[serde(rename = "wait_s", with = "duration_secs")]
+        wait: Duration,
+    },
+}
+
+/// Whether an incorrect answer was too high or too low.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+impl Verdict {
+    /// Parses the response of the website to a submission, as printed by aoc-cli.
+    /// Returns [`None`] if the response is not a verdict on the answer.
+    #[must_use]
+    pub fn parse(response: &str) -> Option<Self> {
+        let response = collapse_whitespace(response);
+
+        if response.contains("That's the right answer") {
+            Some(Self::Correct)
+        } else if response.contains("That's not the right answer") {
+            let hint = if response.contains("too high") {
+                Some(Hint::TooHigh)
+            } else if response.contains("too low") {
+                Some(Hint::TooLow)
+            } else {
+                None
+            };
+            Some(Self::Incorrect { hint })
+        } else if response.contains("You don't seem to be solving the right level") {
+            Some(Self::AlreadySolved)
+        } else if response.contains("You gave an answer too recently") {
+            Some(Self::TooSoon {
+                wait: parse_cooldown(&response).unwrap_or_default(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::Incorrect { hint: None } => write!(f, "incorrect"),
+            Self::Incorrect {
+                hint: Some(Hint::TooHigh),
+            } => write!(f, "too high"),
+            Self::Incorrect {
+                hint: Some(Hint::TooLow),
+            } => write!(f, "too low"),
+            Self::AlreadySolved => write!(f, "already solved"),
+            Self::TooSoon { wait } => write!(f, "too soon, {} left to wait", format_wait(*wait)),
+        }
+    }
+}
+
+/// An answer that was submitted, with the verdict of the website.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    pub day: Day,
+    pub part: u8,
+    pub answer: String,
+    /// Seconds since the unix epoch.
+    pub submitted_at: u64,
+    #[serde(flatten)]
+    pub verdict: Verdict,
+    /// Time to wait before the next submission, if the website asked for it.
+    #[serde(
+        default,
+        rename = "cooldown_s",
+        skip_serializing_if = "Option::is_none",
+        with = "duration_secs::option"
+    )]
+    pub cooldown: Option<Duration>,
+}
+
+impl Submission {
+    /// Creates the ledger entry for `answer` from the `response` of the website.
+    /// Returns [`None`] if the response is not a verdict on the answer.
+    #[must_use]
+    pub fn new(day: Day, part: u8, answer: &str, response: &str, now: SystemTime) -> Option<Self> {
+        Some(Self {
+            day,
+            part,
+            answer: answer.to_string(),
+            submitted_at: now.duration_since(UNIX_EPOCH).map_or(0, |x| x.as_secs()),
+            verdict: Verdict::parse(response)?,
+            cooldown: parse_cooldown(&collapse_whitespace(response)),
+        })
+    }
+
+    fn cooldown_end(&self) -> Option<SystemTime> {
+        self.cooldown
+            .map(|cooldown| UNIX_EPOCH + Duration::from_secs(self.submitted_at) + cooldown)
+    }
+}
+
+/// Why a submission was not sent.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Refusal {
+    /// The part was already solved, with this answer if it is known.
+    Solved(Option<String>),
+    /// The answer was submitted before and got this verdict.
+    Duplicate(Verdict),
+    /// The answer is not below an earlier answer that was too high.
+    AboveTooHigh(String),
+    /// The answer is not above an earlier answer that was too low.
+    BelowTooLow(String),
+    /// The website asked to wait this much longer.
+    Cooldown(Duration),
+}
+
+impl Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Solved(Some(answer)) => write!(f, "the part was already solved with {answer}"),
+            Self::Solved(None) => write!(f, "the part was already solved"),
+            Self::Duplicate(verdict) => write!(f, "it was submitted before and was {verdict}"),
+            Self::AboveTooHigh(bound) => write!(f, "{bound} was already too high"),
+            Self::BelowTooLow(bound) => write!(f, "{bound} was already too low"),
+            Self::Cooldown(wait) => {
+                write!(f, "the website asked to wait, {} left", format_wait(*wait))
+            }
+        }
+    }
+}
+
+#[must_use]
+pub fn get_path(year: Year) -> String {
+    format!("data/{year}/submissions.jsonl")
+}
+
+/// Reads the ledger of `year`. Returns no submissions if nothing was submitted yet.
+/// Fails with [`Error::Parser`] if a line of the ledger is not a submission.
+pub fn read(year: Year) -> Result<Vec<Submission>, Error> {
+    let path = get_path(year);
+    match fs::read_to_string(&path) {
+        Ok(contents) => parse(&contents, &path),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(Error::io(format!("could not read \"{path}\""))(e)),
+    }
+}
+
+/// Parses the lines of the ledger at `path`, blank lines are skipped.
+fn parse(contents: &str, path: &str) -> Result<Vec<Submission>, Error> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                Error::Parser(format!("could not parse line {} of \"{path}\": {e}", i + 1))
+            })
+        })
+        .collect()
+}
+
+/// Adds `submission` to the ledger of `year`.
+pub fn append(year: Year, submission: &Submission) -> Result<(), Error> {
+    let path = get_path(year);
+    let context = format!("could not write \"{path}\"");
+
+    if let Some(parent) = Path::new(&path).parent() {
+        fs::create_dir_all(parent).map_err(Error::io(&context))?;
+    }
+
+    let line = serde_json::to_string(submission).expect("submissions serialize to json");
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{line}"))
+        .map_err(Error::io(context))
+}
+
+/// Checks `answer` for `part` of `day` against the accepted `answers` of the day and the earlier `submissions`.
+pub fn check(
+    submissions: &[Submission],
+    answers: Option<&Answers>,
+    day: Day,
+    part: u8,
+    answer: &str,
+    now: SystemTime,
+) -> Result<(), Refusal> {
+    if let Some(accepted) = answers.and_then(|x| x.get(part)) {
+        return Err(Refusal::Solved(Some(accepted.to_string())));
+    }
+
+    if is_solved_by(submissions, day, part, None) {
+        let accepted = submissions
+            .iter()
+            .find(|x| x.day == day && x.part == part && x.verdict == Verdict::Correct);
+        return Err(Refusal::Solved(accepted.map(|x| x.answer.clone())));
+    }
+
+    // the cooldown of the website applies to all puzzles.
+    if let Some(end) = submissions.last().and_then(Submission::cooldown_end) {
+        if let Ok(remaining) = end.duration_since(now) {
+            if !remaining.is_zero() {
+                return Err(Refusal::Cooldown(remaining));
+            }
+        }
+    }
+
+    let submissions = submissions
+        .iter()
+        .filter(|x| x.day == day && x.part == part);
+    let value = answer.trim().parse::<i128>().ok();
+
+    for submission in submissions {
+        let bound = submission.answer.trim().parse::<i128>().ok();
+
+        match &submission.verdict {
+            verdict @ Verdict::Incorrect { .. } if submission.answer == answer => {
+                return Err(Refusal::Duplicate(verdict.clone()))
+            }
+            Verdict::Incorrect {
+                hint: Some(Hint::TooHigh),
+            } => {
+                if let (Some(value), Some(bound)) = (value, bound) {
+                    if value >= bound {
+                        return Err(Refusal::AboveTooHigh(submission.answer.clone()));
+                    }
+                }
+            }
+            Verdict::Incorrect {
+                hint: Some(Hint::TooLow),
+            } => {
+                if let (Some(value), Some(bound)) = (value, bound) {
+                    if value <= bound {
+                        return Err(Refusal::BelowTooLow(submission.answer.clone()));
+                    }
+                }
+            }
+            Verdict::Correct
+            | Verdict::AlreadySolved
+            | Verdict::Incorrect { hint: None }
+            | Verdict::TooSoon { .. } => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `part` of `day` is solved, according to its accepted answer in `answers` or the verdicts of `submissions`.
+#[must_use]
+pub fn is_solved(
+    submissions: &[Submission],
+    answers: Option<&Answers>,
+    day: Day,
+    part: u8,
+) -> bool {
+    answers.is_some_and(|x| x.get(part).is_some()) || is_solved_by(submissions, day, part, None)
+}
+
+/// Whether `submissions` show that `part` of `day` was solved, at or before `until` seconds since the unix epoch if given.
+fn is_solved_by(submissions: &[Submission], day: Day, part: u8, until: Option<u64>) -> bool {
+    submissions
+        .iter()
+        .filter(|x| x.day == day && x.part == part && until.is_none_or(|t| x.submitted_at <= t))
+        .any(|x| match x.verdict {
+            Verdict::Correct => true,
+            // the website only means it for part two if part one was solved when it was submitted.
+            Verdict::AlreadySolved => {
+                part == 1 || is_solved_by(submissions, day, part - 1, Some(x.submitted_at))
+            }
+            Verdict::Incorrect { .. } | Verdict::TooSoon { .. } => false,
+        })
+}
+
+/// Parses the time to wait from a response, e.g. "You have 1m 30s left to wait"
+/// or "please wait 5 minutes before trying again".
+fn parse_cooldown(response: &str) -> Option<Duration> {
+    if let Some(start) = response.find("You have ") {
+        let rest = &response[start + "You have ".len()..];
+        let end = rest.find(" left to wait")?;
+        return rest[..end]
+            .split_whitespace()
+            .map(|x| {
+                let (value, unit) = x.split_at(x.find(|c: char| !c.is_ascii_digit())?);
+                let value: u64 = value.parse().ok()?;
+                match unit {
+                    "h" => Some(value * 60 * 60),
+                    "m" => Some(value * 60),
+                    "s" => Some(value),
+                    _ => None,
+                }
+            })
+            .sum::<Option<u64>>()
+            .map(Duration::from_secs);
+    }
+
+    let response = response.to_lowercase();
+    if let Some(start) = response.find("please wait ") {
+        let mut words = response[start + "please wait ".len()..].split_whitespace();
+        let value = match words.next()? {
+            "one" => 1,
+            x => x.parse().ok()?,
+        };
+        return match words.next()? {
+            "minute" | "minutes" => Some(Duration::from_secs(value * 60)),
+            "second" | "seconds" => Some(Duration::from_secs(value)),
+            _ => None,
+        };
+    }
+
+    None
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn format_wait(wait: Duration) -> String {
+    match wait.as_secs() {
+        secs if secs >= 60 => format!("{}m {}s", secs / 60, secs % 60),
+        secs => format!("{secs}s"),
+    }
+}
+
+mod duration_secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_secs())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_secs)
+    }
+
+    pub mod option {
+        use serde::{Deserialize, Deserializer, Serializer};
+        use std::time::Duration;
+
+        pub fn serialize<S: Serializer>(
+            duration: &Option<Duration>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match duration {
+                Some(duration) => serializer.serialize_some(&duration.as_secs()),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Duration>, D::Error> {
+            Option::<u64>::deserialize(deserializer).map(|x| x.map(Duration::from_secs))
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{check, is_solved, parse, parse_cooldown, Hint, Refusal, Submission, Verdict};
+    use crate::day;
+    use crate::template::{answers::Answers, Error};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn submission(answer: &str, response: &str, secs: u64) -> Submission {
+        Submission::new(day!(1), 1, answer, response, at(secs)).unwrap()
+    }
+
+    #[test]
+    fn parses_verdicts() {
+        assert_eq!(
+            Verdict::parse("That's the right answer! You are one gold star closer."),
+            Some(Verdict::Correct)
+        );
+        assert_eq!(
+            Verdict::parse("That's not the right answer; your answer is too\nhigh."),
+            Some(Verdict::Incorrect {
+                hint: Some(Hint::TooHigh)
+            })
+        );
+        assert_eq!(
+            Verdict::parse("You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 1m 30s left to wait."),
+            Some(Verdict::TooSoon {
+                wait: Duration::from_secs(90)
+            })
+        );
+        assert_eq!(
+            Verdict::parse(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            ),
+            Some(Verdict::AlreadySolved)
+        );
+        assert_eq!(Verdict::parse("[ERROR] could not reach the website"), None);
+    }
+
+    #[test]
+    fn parses_cooldown_of_wrong_answers() {
+        assert_eq!(
+            parse_cooldown("Please wait one minute before trying again."),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            parse_cooldown("Because you have guessed incorrectly 4 times on this puzzle, please wait 5 minutes before trying again."),
+            Some(Duration::from_secs(300))
+        );
+    }
+
+    #[test]
+    fn reports_malformed_ledger_lines() {
+        let line = serde_json::to_string(&submission("42", "That's the right answer!", 0)).unwrap();
+        let path = "data/2023/submissions.jsonl";
+
+        let submissions = parse(&format!("{line}\n\n{line}\n"), path).unwrap();
+        assert_eq!(submissions.len(), 2);
+
+        match parse(&format!("{line}\n{{\"day\":1"), path) {
+            Err(Error::Parser(message)) => {
+                assert!(message
+                    .starts_with("could not parse line 2 of \"data/2023/submissions.jsonl\""));
+            }
+            result => panic!("expected a parser error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn refuses_duplicates_and_bounded_answers() {
+        let submissions = [
+            submission(
+                "100",
+                "That's not the right answer; your answer is too high.",
+                0,
+            ),
+            submission(
+                "10",
+                "That's not the right answer; your answer is too low.",
+                100,
+            ),
+            submission("abc", "That's not the right answer.", 200),
+        ];
+        let now = at(1000);
+
+        assert_eq!(
+            check(&submissions, None, day!(1), 1, "abc", now),
+            Err(Refusal::Duplicate(Verdict::Incorrect { hint: None }))
+        );
+        assert_eq!(
+            check(&submissions, None, day!(1), 1, "150", now),
+            Err(Refusal::AboveTooHigh("100".into()))
+        );
+        assert_eq!(
+            check(&submissions, None, day!(1), 1, "10", now),
+            Err(Refusal::Duplicate(Verdict::Incorrect {
+                hint: Some(Hint::TooLow)
+            }))
+        );
+        assert_eq!(
+            check(&submissions, None, day!(1), 1, "5", now),
+            Err(Refusal::BelowTooLow("10".into()))
+        );
+        assert_eq!(check(&submissions, None, day!(1), 1, "50", now), Ok(()));
+        assert_eq!(check(&submissions, None, day!(1), 2, "150", now), Ok(()));
+    }
+
+    #[test]
+    fn refuses_solved_parts() {
+        let answers = Answers::parse("part_one = 42").unwrap();
+        assert_eq!(
+            check(&[], Some(&answers), day!(1), 1, "43", at(0)),
+            Err(Refusal::Solved(Some("42".into())))
+        );
+
+        let submissions = [submission("42", "That's the right answer!", 0)];
+        assert_eq!(
+            check(&submissions, None, day!(1), 1, "43", at(1000)),
+            Err(Refusal::Solved(Some("42".into())))
+        );
+    }
+
+    #[test]
+    fn counts_already_solved_only_after_part_one() {
+        let already_solved =
+            "You don't seem to be solving the right level.  Did you already complete it?";
+        let part_two = |secs| Submission::new(day!(1), 2, "7", already_solved, at(secs)).unwrap();
+
+        // part two was submitted before part one was solved.
+        let submissions = [
+            part_two(0),
+            submission("42", "That's the right answer!", 100),
+        ];
+        assert!(!is_solved(&submissions, None, day!(1), 2));
+        assert_eq!(check(&submissions, None, day!(1), 2, "8", at(1000)), Ok(()));
+
+        let submissions = [
+            submission("42", "That's the right answer!", 0),
+            part_two(100),
+        ];
+        assert!(is_solved(&submissions, None, day!(1), 2));
+        assert_eq!(
+            check(&submissions, None, day!(1), 2, "8", at(1000)),
+            Err(Refusal::Solved(None))
+        );
+
+        let submissions = [submission("42", already_solved, 0)];
+        assert!(is_solved(&submissions, None, day!(1), 1));
+    }
+
+    #[test]
+    fn respects_cooldown() {
+        let submissions = [submission(
+            "50",
+            "You gave an answer too recently. You have 30s left to wait.",
+            1000,
+        )];
+        assert_eq!(
+            check(&submissions, None, day!(1), 1, "50", at(1010)),
+            Err(Refusal::Cooldown(Duration::from_secs(20)))
+        );
+        assert_eq!(
+            check(&submissions, None, day!(1), 1, "50", at(1030)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn stores_verdicts_as_json() {
+        let submission = submission(
+            "100",
+            "That's not the right answer; your answer is too high. Please wait 5 minutes before trying again.",
+            1_701_406_800,
+        );
+        let json = serde_json::to_string(&submission).unwrap();
+        assert_eq!(
+            json,
+            r#"{"day":1,"part":1,"answer":"100","submitted_at":1701406800,"verdict":"incorrect","hint":"too_high","cooldown_s":300}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Submission>(&json).unwrap(),
+            submission
+        );
+    }
+}
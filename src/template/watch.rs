@@ -0,0 +1,70 @@
+/// Module that re-runs a command whenever one of a set of files changes.
+/// Files are polled for changes of their modification time, so no platform-specific watcher is needed.
+use std::fs;
+use std::io::{stdout, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::template::{ANSI_CLEAR, ANSI_ITALIC, ANSI_RESET};
+
+/// Interval in which the watched files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Runs the command created by `command`, and again each time one of `paths` is changed, created or removed.
+/// The screen is cleared before each run. A run that is still in progress when a file changes is killed.
+/// This never returns, the process is stopped with Ctrl+C.
+pub fn watch(paths: &[PathBuf], command: impl Fn() -> Command) -> ! {
+    loop {
+        let snapshot = get_snapshot(paths);
+
+        print!("{ANSI_CLEAR}");
+        let _ = stdout().flush();
+
+        let mut child = command().spawn().ok();
+
+        if child.is_none() {
+            eprintln!("Failed to start the command.");
+        }
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            if get_snapshot(paths) != snapshot {
+                if let Some(child) = &mut child {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                }
+                break;
+            }
+
+            if let Some(finished) = child.as_mut().and_then(|x| x.try_wait().ok().flatten()) {
+                child = None;
+                if !finished.success() {
+                    eprintln!("Command exited with {finished}.");
+                }
+                print_waiting(paths);
+            }
+        }
+
+        // editors may write a file in several steps, give them a moment to finish.
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Modification times of `paths`, [`None`] for files that do not exist.
+fn get_snapshot(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|x| x.modified()).ok())
+        .collect()
+}
+
+fn print_waiting(paths: &[PathBuf]) {
+    let paths: Vec<String> = paths.iter().map(|x| x.display().to_string()).collect();
+    println!(
+        "\n{ANSI_ITALIC}Watching {} for changes...{ANSI_RESET}",
+        paths.join(", ")
+    );
+}